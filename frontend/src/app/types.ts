@@ -8,12 +8,60 @@ export interface AccountData {
     ty: string;
     extra_accounts: string[];
     transactions: Transaction[];
+    counterparties?: CounterpartyFlow[];
+    spam?: SpamSummary;
+    entity?: string;
+    subcategory?: string;
+    tags?: string[];
+    addresses?: EntityAddress[];
+  }
+
+  export interface EntityAddress {
+    name: string;
+    principal?: string;
+    subaccount?: string;
+    account?: string;
+    extra_accounts: string[];
+    transfer_count: number;
+    balance_e8s?: number;
+  }
+
+  export interface SpamSummary {
+    sent_count: number;
+    received_count: number;
+    sent_e8s: number;
+    received_e8s: number;
+    spammer_count: number;
+  }
+
+  export interface CounterpartyFlow {
+    counterparty: string;
+    label?: Label;
+    sent_e8s: number;
+    received_e8s: number;
+    transfer_count: number;
+    first_timestamp_nanos?: number;
+    last_timestamp_nanos?: number;
   }
   
   export interface Transaction {
     op_type: Operation;
     to: string;
     from: string;
+    id?: number;
+    amount_e8s?: number;
+    fee_e8s?: number;
+    memo?: number;
+    timestamp_nanos?: number;
+    from_label?: Label;
+    to_label?: Label;
+    counterparty?: string;
+    counterparty_label?: Label;
+  }
+
+  export interface Label {
+    name: string;
+    ty: string;
   }
   
   export type Operation =
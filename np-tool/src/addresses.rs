@@ -22,34 +22,41 @@ pub const CEXES: &[(&str, &str)] = &[
     ("OKX 2", "d2c6135510eaf107bdc2128ef5962c7db2ae840efdf95b9395cdaf4983942978"),
 ];
 
-pub const FOUNDATION: &[(&str, &str)] = &[
-    ("Genesis 1.3m", "1a481b7aa86fa89029117f0487479f52fe3ee27ad63d48bac78fa60b3ebf9237"),
-    ("Genesis 1.4m", "12b7c1c6269b2021758ed5da65448a4ac3ac9fa0cf015caa4bb4c3e3dc7ca1c1"),
-    ("Genesis 107m", "125013e95bd5e008bd6d26f86f5ddda2b16c382372b3067672505c1f11418817"),
-    ("Genesis 4.1m", "1f5ef4de27f2880dac6409b431ba866170be8b04e87afe95c841ca163d9c3689"),
-    ("Genesis 1.3m", "21b3cb5fccbbb1b4d92c03ace6f16f836e3425cf61ac3b2a9823499a60d5c7b5"),
-    ("Genesis 1.3m", "36039b216d234b39bfc993df251deba6d7481d974f20a7aa4ea8a2aff8c7606e"),
-    ("Genesis 1.4m", "406ae771636e9e6501166f71edb0b61b80a325640048b11b23b3eaf43a5048ed"),
-    ("Genesis 1.3m", "5495612bb400e0dbd51ca4ae28835b3c47f6968127fe54d4aadc8704cc363057"),
-    ("Genesis 1.2m", "54f4a6d3bc831c5217e4e313bca7f2549f6b51b9dc25d77ae045bbb59c8eafaf"),
-    ("Genesis 1.3m", "57c9e0f1111d0aba921adf0056a16771e9a4fd84a6daee21267511b2b3410731"),
-    ("Genesis 2m", "581ebcfa72dbed72cb0d18240d30879ac915df69dba3d1a8cfbb5508bb973df1"),
-    ("Genesis 1.2m", "600bc2e6fa9dcf3543acc22bd8181ca7e11ef3f0a9ce662480fe61524c9bb8c1"),
-    ("Genesis 3.8m", "61529f442b6fc6a2db07f4dc446b255e6702aa95ed8bb2fa5c38cb04358eea65"),
-    ("Genesis 1.3m", "65675acf9e0752bbc58893b284e2c7558db61bfdbefe63c055bb69305f0da17e"),
-    ("Genesis 1.3m", "6a6fe5e9936747faadb472f3d3790830c372eb4ab7039d207059bf34eacf4de9"),
-    ("Genesis 1.2m", "78a6c47cc1e153e1b63eafcd471d5e6de1365592e73d0c41931461fd97271d03"),
-    ("Genesis 3.8m", "860c10fd2e96106edccb48e657b265517fda354a4588a52a13384dd58ffcaab2"),
-    ("Genesis 1.4m", "91ee71b84b7603de21bcf6cc1857a7cb91d12062d91f95013d300e0eb35e6d52"),
-    ("Genesis 1.5m", "ad2d4c5c3b70fa1289c7b45ef252d608f8d70f0d9b8198d6a6d0c4f5bdfc9c65"),
-    ("Genesis 1.5m", "b0c25df9be777bb84a2b8ddec02bbf42bfef588c44bc7fd483a515725cd68830"),
-    ("Genesis 1.2m", "b30d4f02181bd81dbe5ef9f22b33a7d7a5fe61884964fc697721ca259cfd3c41"),
-    ("Genesis 3.5m", "b93164c6ae75984345a3d47fa9877749acb19fee032ec6aac67cb3ee6100f302"),
-    ("Genesis 1.2m", "b9cdb3a04e388736de3eea9426f4f83e79c90a6602b0805746ff7991e546df9f"),
-    ("Genesis 1.3m", "d5336412e2107f4b0502234128dbc11ccf53221ae67bae5109eb4be11bb9babb"),
-    ("Genesis 1.3m", "d539266d9e7784304ceb7b72a729794004646e4a39a56b51c3e911a698bbdd8d"),
-    ("Genesis 1.4m", "f7d23ad118bab9eae59055a98addd2a1738cc281fa1dc7ca4568e8e661e21283"),
-];
+// name, ICP received at genesis, address
+pub type GenesisAccount = (&'static str, u64, &'static str);
+
+// grouped by subcategory
+pub const FOUNDATION: &[(&str, &[GenesisAccount])] = &[(
+    "Genesis",
+    &[
+        ("Genesis 1.3m 1", 1_300_000, "1a481b7aa86fa89029117f0487479f52fe3ee27ad63d48bac78fa60b3ebf9237"),
+        ("Genesis 1.4m 1", 1_400_000, "12b7c1c6269b2021758ed5da65448a4ac3ac9fa0cf015caa4bb4c3e3dc7ca1c1"),
+        ("Genesis 107m", 107_000_000, "125013e95bd5e008bd6d26f86f5ddda2b16c382372b3067672505c1f11418817"),
+        ("Genesis 4.1m", 4_100_000, "1f5ef4de27f2880dac6409b431ba866170be8b04e87afe95c841ca163d9c3689"),
+        ("Genesis 1.3m 2", 1_300_000, "21b3cb5fccbbb1b4d92c03ace6f16f836e3425cf61ac3b2a9823499a60d5c7b5"),
+        ("Genesis 1.3m 3", 1_300_000, "36039b216d234b39bfc993df251deba6d7481d974f20a7aa4ea8a2aff8c7606e"),
+        ("Genesis 1.4m 2", 1_400_000, "406ae771636e9e6501166f71edb0b61b80a325640048b11b23b3eaf43a5048ed"),
+        ("Genesis 1.3m 4", 1_300_000, "5495612bb400e0dbd51ca4ae28835b3c47f6968127fe54d4aadc8704cc363057"),
+        ("Genesis 1.2m 1", 1_200_000, "54f4a6d3bc831c5217e4e313bca7f2549f6b51b9dc25d77ae045bbb59c8eafaf"),
+        ("Genesis 1.3m 5", 1_300_000, "57c9e0f1111d0aba921adf0056a16771e9a4fd84a6daee21267511b2b3410731"),
+        ("Genesis 2m", 2_000_000, "581ebcfa72dbed72cb0d18240d30879ac915df69dba3d1a8cfbb5508bb973df1"),
+        ("Genesis 1.2m 2", 1_200_000, "600bc2e6fa9dcf3543acc22bd8181ca7e11ef3f0a9ce662480fe61524c9bb8c1"),
+        ("Genesis 3.8m 1", 3_800_000, "61529f442b6fc6a2db07f4dc446b255e6702aa95ed8bb2fa5c38cb04358eea65"),
+        ("Genesis 1.3m 6", 1_300_000, "65675acf9e0752bbc58893b284e2c7558db61bfdbefe63c055bb69305f0da17e"),
+        ("Genesis 1.3m 7", 1_300_000, "6a6fe5e9936747faadb472f3d3790830c372eb4ab7039d207059bf34eacf4de9"),
+        ("Genesis 1.2m 3", 1_200_000, "78a6c47cc1e153e1b63eafcd471d5e6de1365592e73d0c41931461fd97271d03"),
+        ("Genesis 3.8m 2", 3_800_000, "860c10fd2e96106edccb48e657b265517fda354a4588a52a13384dd58ffcaab2"),
+        ("Genesis 1.4m 3", 1_400_000, "91ee71b84b7603de21bcf6cc1857a7cb91d12062d91f95013d300e0eb35e6d52"),
+        ("Genesis 1.5m 1", 1_500_000, "ad2d4c5c3b70fa1289c7b45ef252d608f8d70f0d9b8198d6a6d0c4f5bdfc9c65"),
+        ("Genesis 1.5m 2", 1_500_000, "b0c25df9be777bb84a2b8ddec02bbf42bfef588c44bc7fd483a515725cd68830"),
+        ("Genesis 1.2m 4", 1_200_000, "b30d4f02181bd81dbe5ef9f22b33a7d7a5fe61884964fc697721ca259cfd3c41"),
+        ("Genesis 3.5m", 3_500_000, "b93164c6ae75984345a3d47fa9877749acb19fee032ec6aac67cb3ee6100f302"),
+        ("Genesis 1.2m 5", 1_200_000, "b9cdb3a04e388736de3eea9426f4f83e79c90a6602b0805746ff7991e546df9f"),
+        ("Genesis 1.3m 8", 1_300_000, "d5336412e2107f4b0502234128dbc11ccf53221ae67bae5109eb4be11bb9babb"),
+        ("Genesis 1.3m 9", 1_300_000, "d539266d9e7784304ceb7b72a729794004646e4a39a56b51c3e911a698bbdd8d"),
+        ("Genesis 1.4m 4", 1_400_000, "f7d23ad118bab9eae59055a98addd2a1738cc281fa1dc7ca4568e8e661e21283"),
+    ],
+)];
 
 pub const IDENTIFIED: &[(&str, &str)] = &[
     ("Austin Fatheree", "83a06afc3e0707f633ae839c1e4f756846a13c6bf1d005499a7f50725cf9f8db"),
@@ -269,98 +276,151 @@ pub const SNSES: &[(&str, &str)] = &[
     ("Yuku AI", "auadn-oqaaa-aaaaq-aacya-cai"),
 ];
 
-pub const SNS_PARTICIPANTS: &[(&str, &str)] = &[
-    //
-    // SNS Participants
-    //
-    // Many
-    ("SNS: DOLR 2k, WTN 5k", "vgrm6-ur624-kl4qd-3sukh-wssor-ztoox-ecgbu-ebvn5-f4hgu-quqyk-pae"),
-    ("SNS: WTN 12k, Yuku 12k, Alice 4k", "3qzrp-z7r45-vzitp-ylj45-brcdc-vhiyg-xew4i-wpeqg-b2gf6-2xzzd-yqe"),
-    ("SNS: DOLR 5k, Yuku 8k", "mlnie-bxsfa-gv7tu-ehhvv-7i24g-b2cnn-qbxnk-q4css-g24an-yinaf-lae"),
-    ("SNS: Alice 3k, NFIDW 2k", "27byn-shdjj-bt2ec-jz3jq-hnkgw-6qshf-fi7nj-xixnv-xbaq6-by7tz-kae"),
-    ("SNS: Alice 10k, WTN 20k", "cld52-vm6st-5ulwe-yperp-iwvft-gqt7a-jrbpm-pkdcl-yszk3-zyxvb-wae"),
-    ("SNS: ICPSwap 50k, ICVC 17k", "tfxmx-hpuo2-z2faw-ii37v-wzi4w-o2lre-oup6d-bdhhx-ebglo-ujqzl-5qe"),
-    // Alice
-    ("Alice SNS 3.5k", "5boyw-rp6w3-gn66y-kqgse-igw5t-xmwjh-ums35-pmicl-5yxiz-uhfjv-cae"),
-    ("Alice SNS 2.5k", "kwhw3-kexxs-hh45h-w4s7e-tzjxu-zqvln-edkon-kwoh6-5xvtx-iaeng-yqe"),
-    ("Alice SNS 2k", "ww2jx-jrz3a-ycha4-3qvql-hgwim-juh2o-dquln-jrcie-s5wrl-xyims-aqe"),
-    ("Alice SNS 2k", "7san4-okna2-mlprv-2wlqi-w6bz2-u75xy-pkjlw-jsv32-guzes-r3ojs-2qe"),
-    ("Alice SNS 2k", "th65x-be7bq-thkki-clkqi-kpt5z-td2ji-rnz7r-jzk6i-zsj4o-fd3yb-uae"),
-    ("Alice SNS 1k", "wtio3-vyvwo-djf2j-rmh6f-5r5fh-imkir-f6k6w-3nwvm-w7nvg-g4gpa-tae"),
-    // DOLR
-    ("DOLR SNS 150k", "n4gtt-k6lm6-26bnm-iy7nd-syyub-zaz7y-ikmi6-74w3u-7c3wk-iuqxt-rae"),
-    ("DOLR SNS 93k", "74ekp-mnvbw-gdabj-3ho4b-jao7e-5f7iu-27xik-ouf6s-w3u22-jmzer-yqe"),
-    ("DOLR SNS 60k", "wwyo5-vrahh-jwa74-3m6kj-jqbia-jbebm-7vtyd-uvqem-wk3zw-djpci-vqe"),
-    ("DOLR SNS 6k", "lvsje-wfazq-xzrha-quuz7-quwur-67jl7-nbb7v-5llqf-vclab-slswv-rae"),
-    ("DOLR SNS 5k", "bmhrf-hq2zu-dlrcg-6d375-xc2x6-ljmmx-g36gu-bz2eb-sm6m3-lfdgk-uqe"),
-    ("DOLR SNS 4k", "r4ixk-5nzuy-qpjsj-3srvi-qr6yj-iydup-7kt3j-2rdf4-dfeok-5nhnk-iqe"),
-    // FomoWell
-    ("FomoWell SNS 9k", "lqg4j-7ia6a-35xbl-cqyge-ygedg-hreu7-ey23r-o75da-sjysa-ul2hd-gae"),
-    ("FomoWell SNS 9k", "2ilme-ityi5-x5tnr-v6fqo-ptwgz-sz3jy-kfnm7-con4a-7xtsd-24qcx-bqe"),
-    ("FomoWell SNS 8k", "jxagt-t5peo-f37ia-sa2qj-uxck7-xuaky-u4dmm-mjlyy-jzdvm-zlh7z-pqe"),
-    ("FomoWell SNS 7k", "wbgeu-najtu-dnbyg-aanjq-cnjbo-iv4ww-5zqhv-u3i6q-5ibu7-tmpmu-lae"),
-    ("FomoWell SNS 5k", "4uuwq-a5be5-3xmy2-augbb-qkwkt-myamy-eyyi6-bjr2d-xealc-pj6kw-eae"),
-    ("FomoWell SNS 5k", "aympm-5rio2-3ia3x-3akvi-q3i3n-z3uop-vhkpl-aqals-7e63d-fhkbe-vae"),
-    ("FomoWell SNS 5k", "ygge7-et7ta-yfem4-3bgzj-mtr7s-yuacx-3w3jv-adech-wumey-djgtc-gae"),
-    ("FomoWell SNS 5k", "r2gx5-ybbxe-5ydur-unxzq-tf4md-chvtj-63pw3-hgcxk-2berj-5vdvv-kqe"),
-    ("FomoWell SNS 4k", "j45sz-ou2ji-oi6va-fzvnl-n2div-kvmc6-rvciy-n2rbi-aw2zk-rq53y-wqe"),
-    ("FomoWell SNS 3k", "nta2n-ezhdg-cqdwy-s2rui-qfqyz-jjfeq-3vklv-2626u-wzzxi-43psa-zae"),
-    ("FomoWell SNS 2k", "32opp-houiq-hswoc-bezg2-kizdu-rrprd-wslwm-7ktne-r2gyy-zan6b-dqe"),
-    ("FomoWell SNS 2k", "4zaho-oy6oh-vgswm-axccn-nbpdb-4mraq-iaryu-kvhgx-tilag-okte2-vae"),
-    ("FomoWell SNS 2k", "mvkka-yg42z-hinjk-yo4w7-uzpik-iqyme-utynn-m6t4z-quhuy-37eju-wae"),
-    ("FomoWell SNS 1k", "7jnbz-ta45w-3vvd2-hghxy-cvext-rwpxo-7342o-5kkjt-q5puz-m7v7i-tqe"),
-    // FuelEV
-    ("FuelEV SNS 7.5k", "mxdob-jhdxa-6oxow-rjk5x-b5frs-k7kno-oxics-tmcky-5f62q-n6biy-bae"),
-    ("FuelEV SNS 6.5k", "rxom7-w4otd-lfmpa-c4wcs-gugja-2bvdz-x3rqy-ytcu3-b3lew-lxdq3-aqe"),
-    ("FuelEV SNS 6k", "yvm75-incaw-n5bvx-so7pi-3x255-harni-avdmu-wwwuq-34dnt-j3ngi-tae"),
-    ("FuelEV SNS 6k", "nwzvm-zhkur-grqnz-dmd7t-vxcpm-hm6pw-guwei-jehi3-lbhjq-dubuo-hqe"),
-    ("FuelEV SNS 6k", "u5ha5-f6l7r-2lccy-5juy3-cjy6j-5zwdu-aj2jq-k42nd-q6754-g2nlv-vqe"),
-    ("FuelEV SNS 6k", "k5btm-ye75h-tle5b-6j7js-4yjol-clmr7-waklm-dsnuc-ztdjz-bcumn-kqe"),
-    ("FuelEV SNS 4k", "zfeqd-7bvoc-w7uki-rsbe5-v2hmh-qdljb-dilke-voc7b-edgr3-gx2rq-eae"),
-    ("FuelEV SNS 5k", "nkgwd-q2scf-xqmet-d6cqr-gjy7x-zl5hv-ou3am-cc7qv-knsun-pafav-rqe"),
-    ("FuelEV SNS 5k", "7c6eh-ijdxj-clnm5-o5id4-okihg-7t7py-awb54-3xkgp-fozce-2ow3y-yae"),
-    ("FuelEV SNS 6k", "dbxmv-nuiwm-by426-s4xho-z2yc3-pnx5e-lqg7d-lckoc-v3bwx-jk2rz-yae"),
-    // ICPEx
-    ("ICPEx SNS 30k", "fznfr-p3puw-izxeh-ud4qr-k26q2-pdgfh-vxmr5-kswww-okpb2-cohna-5ae"),
-    ("ICPEx SNS 17k", "meavt-eugdu-w4zjb-gr3qe-nvqxl-dv6pl-t256f-aussh-xpp6d-wzy4c-rae"),
-    ("ICPEx SNS 12k", "e4s3u-6prhs-ll5vk-fkcel-33xw7-5t6d7-raqjs-2js2b-zblrn-smowm-eae"),
-    ("ICPEx SNS 11k", "jusdz-4xamz-xdnq5-uso3u-hralh-a55cp-zyek4-wnaxo-7xpkr-jxmwf-jae"),
-    ("ICPEx SNS 10k", "w6ezf-7ywlx-gofjr-zek2o-v32pm-fsoj4-cim2x-cbdmi-jrtlb-skfez-3ae"),
-    ("ICPEx SNS 10k", "q5cqr-x4pj3-n7feb-ratsg-y7hwi-bwpja-xs7b3-f5m65-m6jfl-yodd2-vqe"),
-    // ICPSwap
-    ("ICPSwap SNS 26k", "eelqg-w3mgp-4btdz-6fagh-xcbgs-4pik7-pzolk-f3sdw-qajex-3hnhr-oae"),
-    ("ICPSwap SNS 10k", "cvtfn-74z2v-v2m5k-ohidc-gdb3e-hde7g-hvsqc-gwdmx-oef7y-vub72-oae"),
-    ("ICPSwap SNS 10k", "6n7h6-6cvld-44lpq-wvpoj-mk6ur-slbhz-mqu4w-yerqn-btnzc-hwwdy-mqe"),
-    ("ICPSwap SNS 9k", "w23jp-jvtce-32ob6-lhxwb-sjrbf-tyq7q-oifov-ijy24-gz6jf-gfogx-bqe"),
-    // ICVC
-    ("ICVC SNS 23k", "2sgpc-zn4to-caeip-fqikj-rhfcd-rzelp-vc6we-3ud7d-f4fxf-mui4v-5qe"),
-    ("ICVC SNS 20k", "6o2ns-jjwx5-wcwrk-3jrj6-qbcz7-dvolt-cmi4d-cil55-ehniw-bxtmt-hae"),
-    ("ICVC SNS 20k", "rnt66-opaox-kkvbo-mvmwk-yavr3-6bcrp-ccxqf-auunj-mriwj-5eeau-fae"),
-    ("ICVC SNS 20k", "vctcx-gughm-cy4ed-4z6pt-6avjj-ngym4-uvwm2-kb56h-lghvd-2izv3-fqe"),
-    ("ICVC SNS 17k", "5tzzh-kqopl-zychr-jjyoi-cpvu5-y2ai7-ggf3n-icajy-bszp6-2qenk-lae"),
-    // NFIDW
-    ("NFIDW SNS 8k", "bxnp4-ym2h2-nrdxf-l5wgq-agnjv-2dei4-26s54-welyl-wk3ry-sfr4w-qqe"),
-    ("NFIDW SNS 8k", "3rxla-jua5q-xopzh-52v2p-5ih5h-s4yvq-ro2co-gdnzg-xiznr-5ubhj-3qe"),
-    ("NFIDW SNS 6.5k", "24amu-ld2uy-2klfg-kxyhq-ahwsc-vdciv-emymv-g3khl-fsawa-7ekoq-sqe"),
-    ("NFIDW SNS 5k", "ojna7-4o6fi-f4pzd-zu5m2-m5na4-miajz-kz65q-ecmmh-34j43-cfuun-gqe"),
-    ("NFIDW SNS 2k", "v6zy5-xc6h3-oduqq-rfib4-cc4n2-vyeip-4fthl-7qjir-oke3k-ftpvv-3qe"),
-    // WaterNeuron
-    ("WaterNeuron SNS 117k", "ll6pj-3wsrz-glcqo-bhjl7-s7wsj-eaykn-sllr5-4klcj-wxgbe-jm2ei-zqe"),
-    ("WaterNeuron SNS 50k", "zvcax-7pun5-oy64t-aynez-yo2jp-ftieg-otq24-ohoxk-6nlth-64fxt-tae"),
-    ("WaterNeuron SNS 34k", "ifdcz-ditqo-tojui-ncj6w-tukjj-4ujn5-j5ibk-ksch7-abkr5-pd22f-fae"),
-    ("WaterNeuron SNS 6k", "k57i6-rqxsp-3w5kd-oictq-iokt4-uqzvz-yophv-rkfkk-bnqcd-v34ie-yae"),
-    ("WaterNeuron SNS 5k", "ujszi-xic4g-vwnse-qpaqj-ybjvs-bfosw-qnzrt-atp4z-sovvc-4i4ak-wqe"),
-    ("WaterNeuron SNS 4k", "ulvvb-xozrw-cco4f-ucqug-wuiwd-iyfu5-36fgm-fnv4x-jw3j5-ey6c4-mqe"),
-    ("WaterNeuron SNS 3k", "ml6n5-esoaq-crnni-5vg4t-xolbb-clnn2-cfaud-f5i4c-rajya-fzeaz-yqe"),
-    ("WaterNeuron SNS 3k", "k4ra3-t2hvw-g3ww3-zdtx6-pqmj3-tveyx-ajjdl-5dqsv-zygbq-zhab6-6ae"),
-    // Yuku AI
-    ("Yuku SNS 30k", "pcuxr-ctpat-c7m2c-seh76-6bcez-nc5r3-rwbhf-seyrq-ntxnf-iqerm-lae"),
-    ("Yuku SNS 6k", "hk7yv-x7zw2-t2dep-sgahc-wnffi-qszuw-4mm5e-faax3-6mqpi-qmlii-7qe"),
-    ("Yuku SNS 5k", "y7bw2-cqsrh-xehac-gbcya-366ov-k2red-g6imq-ylwrj-wjoid-gsj6v-sae"),
-    ("Yuku SNS 2k", "elag7-zzhos-zwodk-cd4uv-rzx7t-extc6-yxv6g-mq7z6-kn3jt-u56ba-bae"),
-    ("Yuku SNS 2k", "wgn2s-bv3mc-ayri6-xqnuu-5zue6-jsicj-s6mcd-zshsi-utkvg-26w2n-2ae"),
-    ("Yuku SNS 2k", "aqzai-meyuz-xpeyo-zcxc2-ydfyk-ssqmt-x7tuj-4f7tj-3xkka-zsjaq-oae"),
-    ("Yuku SNS 2k", "olskz-65jdj-vbl6r-erszi-dfe4z-i3tl5-t43x6-fg25v-h53ww-wpt34-oae"),
+// ICRC-1 ledgers and their index canisters fetched by the icrc command by default
+pub const TOKEN_LEDGERS: &[(&str, &str, &str)] = &[
+    ("ckBTC", "mxzaz-hqaaa-aaaar-qaada-cai", "n5wcd-faaaa-aaaar-qaaea-cai"),
+    ("ckETH", "ss2fx-dyaaa-aaaar-qacoq-cai", "s3zol-vqaaa-aaaar-qacpa-cai"),
+    ("ckUSDC", "xevnm-gaaaa-aaaar-qafnq-cai", "xrs4b-hiaaa-aaaar-qafoa-cai"),
+];
+
+// short SNS names used in SNS_PARTICIPANTS
+pub const SNS_ALIASES: &[(&str, &str)] =
+    &[("DOLR", "DOLR AI"), ("NFIDW", "NFID Wallet"), ("WTN", "WaterNeuron"), ("Yuku", "Yuku AI")];
+
+// grouped by subcategory
+pub const SNS_PARTICIPANTS: &[(&str, &[(&str, &str)])] = &[
+    (
+        "Many",
+        &[
+            ("SNS: DOLR 2k, WTN 5k", "vgrm6-ur624-kl4qd-3sukh-wssor-ztoox-ecgbu-ebvn5-f4hgu-quqyk-pae"),
+            ("SNS: WTN 12k, Yuku 12k, Alice 4k", "3qzrp-z7r45-vzitp-ylj45-brcdc-vhiyg-xew4i-wpeqg-b2gf6-2xzzd-yqe"),
+            ("SNS: DOLR 5k, Yuku 8k", "mlnie-bxsfa-gv7tu-ehhvv-7i24g-b2cnn-qbxnk-q4css-g24an-yinaf-lae"),
+            ("SNS: Alice 3k, NFIDW 2k", "27byn-shdjj-bt2ec-jz3jq-hnkgw-6qshf-fi7nj-xixnv-xbaq6-by7tz-kae"),
+            ("SNS: Alice 10k, WTN 20k", "cld52-vm6st-5ulwe-yperp-iwvft-gqt7a-jrbpm-pkdcl-yszk3-zyxvb-wae"),
+            ("SNS: ICPSwap 50k, ICVC 17k", "tfxmx-hpuo2-z2faw-ii37v-wzi4w-o2lre-oup6d-bdhhx-ebglo-ujqzl-5qe"),
+        ],
+    ),
+    (
+        "Alice",
+        &[
+            ("Alice SNS 3.5k", "5boyw-rp6w3-gn66y-kqgse-igw5t-xmwjh-ums35-pmicl-5yxiz-uhfjv-cae"),
+            ("Alice SNS 2.5k", "kwhw3-kexxs-hh45h-w4s7e-tzjxu-zqvln-edkon-kwoh6-5xvtx-iaeng-yqe"),
+            ("Alice SNS 2k", "ww2jx-jrz3a-ycha4-3qvql-hgwim-juh2o-dquln-jrcie-s5wrl-xyims-aqe"),
+            ("Alice SNS 2k", "7san4-okna2-mlprv-2wlqi-w6bz2-u75xy-pkjlw-jsv32-guzes-r3ojs-2qe"),
+            ("Alice SNS 2k", "th65x-be7bq-thkki-clkqi-kpt5z-td2ji-rnz7r-jzk6i-zsj4o-fd3yb-uae"),
+            ("Alice SNS 1k", "wtio3-vyvwo-djf2j-rmh6f-5r5fh-imkir-f6k6w-3nwvm-w7nvg-g4gpa-tae"),
+        ],
+    ),
+    (
+        "DOLR",
+        &[
+            ("DOLR SNS 150k", "n4gtt-k6lm6-26bnm-iy7nd-syyub-zaz7y-ikmi6-74w3u-7c3wk-iuqxt-rae"),
+            ("DOLR SNS 93k", "74ekp-mnvbw-gdabj-3ho4b-jao7e-5f7iu-27xik-ouf6s-w3u22-jmzer-yqe"),
+            ("DOLR SNS 60k", "wwyo5-vrahh-jwa74-3m6kj-jqbia-jbebm-7vtyd-uvqem-wk3zw-djpci-vqe"),
+            ("DOLR SNS 6k", "lvsje-wfazq-xzrha-quuz7-quwur-67jl7-nbb7v-5llqf-vclab-slswv-rae"),
+            ("DOLR SNS 5k", "bmhrf-hq2zu-dlrcg-6d375-xc2x6-ljmmx-g36gu-bz2eb-sm6m3-lfdgk-uqe"),
+            ("DOLR SNS 4k", "r4ixk-5nzuy-qpjsj-3srvi-qr6yj-iydup-7kt3j-2rdf4-dfeok-5nhnk-iqe"),
+        ],
+    ),
+    (
+        "FomoWell",
+        &[
+            ("FomoWell SNS 9k", "lqg4j-7ia6a-35xbl-cqyge-ygedg-hreu7-ey23r-o75da-sjysa-ul2hd-gae"),
+            ("FomoWell SNS 9k", "2ilme-ityi5-x5tnr-v6fqo-ptwgz-sz3jy-kfnm7-con4a-7xtsd-24qcx-bqe"),
+            ("FomoWell SNS 8k", "jxagt-t5peo-f37ia-sa2qj-uxck7-xuaky-u4dmm-mjlyy-jzdvm-zlh7z-pqe"),
+            ("FomoWell SNS 7k", "wbgeu-najtu-dnbyg-aanjq-cnjbo-iv4ww-5zqhv-u3i6q-5ibu7-tmpmu-lae"),
+            ("FomoWell SNS 5k", "4uuwq-a5be5-3xmy2-augbb-qkwkt-myamy-eyyi6-bjr2d-xealc-pj6kw-eae"),
+            ("FomoWell SNS 5k", "aympm-5rio2-3ia3x-3akvi-q3i3n-z3uop-vhkpl-aqals-7e63d-fhkbe-vae"),
+            ("FomoWell SNS 5k", "ygge7-et7ta-yfem4-3bgzj-mtr7s-yuacx-3w3jv-adech-wumey-djgtc-gae"),
+            ("FomoWell SNS 5k", "r2gx5-ybbxe-5ydur-unxzq-tf4md-chvtj-63pw3-hgcxk-2berj-5vdvv-kqe"),
+            ("FomoWell SNS 4k", "j45sz-ou2ji-oi6va-fzvnl-n2div-kvmc6-rvciy-n2rbi-aw2zk-rq53y-wqe"),
+            ("FomoWell SNS 3k", "nta2n-ezhdg-cqdwy-s2rui-qfqyz-jjfeq-3vklv-2626u-wzzxi-43psa-zae"),
+            ("FomoWell SNS 2k", "32opp-houiq-hswoc-bezg2-kizdu-rrprd-wslwm-7ktne-r2gyy-zan6b-dqe"),
+            ("FomoWell SNS 2k", "4zaho-oy6oh-vgswm-axccn-nbpdb-4mraq-iaryu-kvhgx-tilag-okte2-vae"),
+            ("FomoWell SNS 2k", "mvkka-yg42z-hinjk-yo4w7-uzpik-iqyme-utynn-m6t4z-quhuy-37eju-wae"),
+            ("FomoWell SNS 1k", "7jnbz-ta45w-3vvd2-hghxy-cvext-rwpxo-7342o-5kkjt-q5puz-m7v7i-tqe"),
+        ],
+    ),
+    (
+        "FuelEV",
+        &[
+            ("FuelEV SNS 7.5k", "mxdob-jhdxa-6oxow-rjk5x-b5frs-k7kno-oxics-tmcky-5f62q-n6biy-bae"),
+            ("FuelEV SNS 6.5k", "rxom7-w4otd-lfmpa-c4wcs-gugja-2bvdz-x3rqy-ytcu3-b3lew-lxdq3-aqe"),
+            ("FuelEV SNS 6k", "yvm75-incaw-n5bvx-so7pi-3x255-harni-avdmu-wwwuq-34dnt-j3ngi-tae"),
+            ("FuelEV SNS 6k", "nwzvm-zhkur-grqnz-dmd7t-vxcpm-hm6pw-guwei-jehi3-lbhjq-dubuo-hqe"),
+            ("FuelEV SNS 6k", "u5ha5-f6l7r-2lccy-5juy3-cjy6j-5zwdu-aj2jq-k42nd-q6754-g2nlv-vqe"),
+            ("FuelEV SNS 6k", "k5btm-ye75h-tle5b-6j7js-4yjol-clmr7-waklm-dsnuc-ztdjz-bcumn-kqe"),
+            ("FuelEV SNS 4k", "zfeqd-7bvoc-w7uki-rsbe5-v2hmh-qdljb-dilke-voc7b-edgr3-gx2rq-eae"),
+            ("FuelEV SNS 5k", "nkgwd-q2scf-xqmet-d6cqr-gjy7x-zl5hv-ou3am-cc7qv-knsun-pafav-rqe"),
+            ("FuelEV SNS 5k", "7c6eh-ijdxj-clnm5-o5id4-okihg-7t7py-awb54-3xkgp-fozce-2ow3y-yae"),
+            ("FuelEV SNS 6k", "dbxmv-nuiwm-by426-s4xho-z2yc3-pnx5e-lqg7d-lckoc-v3bwx-jk2rz-yae"),
+        ],
+    ),
+    (
+        "ICPEx",
+        &[
+            ("ICPEx SNS 30k", "fznfr-p3puw-izxeh-ud4qr-k26q2-pdgfh-vxmr5-kswww-okpb2-cohna-5ae"),
+            ("ICPEx SNS 17k", "meavt-eugdu-w4zjb-gr3qe-nvqxl-dv6pl-t256f-aussh-xpp6d-wzy4c-rae"),
+            ("ICPEx SNS 12k", "e4s3u-6prhs-ll5vk-fkcel-33xw7-5t6d7-raqjs-2js2b-zblrn-smowm-eae"),
+            ("ICPEx SNS 11k", "jusdz-4xamz-xdnq5-uso3u-hralh-a55cp-zyek4-wnaxo-7xpkr-jxmwf-jae"),
+            ("ICPEx SNS 10k", "w6ezf-7ywlx-gofjr-zek2o-v32pm-fsoj4-cim2x-cbdmi-jrtlb-skfez-3ae"),
+            ("ICPEx SNS 10k", "q5cqr-x4pj3-n7feb-ratsg-y7hwi-bwpja-xs7b3-f5m65-m6jfl-yodd2-vqe"),
+        ],
+    ),
+    (
+        "ICPSwap",
+        &[
+            ("ICPSwap SNS 26k", "eelqg-w3mgp-4btdz-6fagh-xcbgs-4pik7-pzolk-f3sdw-qajex-3hnhr-oae"),
+            ("ICPSwap SNS 10k", "cvtfn-74z2v-v2m5k-ohidc-gdb3e-hde7g-hvsqc-gwdmx-oef7y-vub72-oae"),
+            ("ICPSwap SNS 10k", "6n7h6-6cvld-44lpq-wvpoj-mk6ur-slbhz-mqu4w-yerqn-btnzc-hwwdy-mqe"),
+            ("ICPSwap SNS 9k", "w23jp-jvtce-32ob6-lhxwb-sjrbf-tyq7q-oifov-ijy24-gz6jf-gfogx-bqe"),
+        ],
+    ),
+    (
+        "ICVC",
+        &[
+            ("ICVC SNS 23k", "2sgpc-zn4to-caeip-fqikj-rhfcd-rzelp-vc6we-3ud7d-f4fxf-mui4v-5qe"),
+            ("ICVC SNS 20k", "6o2ns-jjwx5-wcwrk-3jrj6-qbcz7-dvolt-cmi4d-cil55-ehniw-bxtmt-hae"),
+            ("ICVC SNS 20k", "rnt66-opaox-kkvbo-mvmwk-yavr3-6bcrp-ccxqf-auunj-mriwj-5eeau-fae"),
+            ("ICVC SNS 20k", "vctcx-gughm-cy4ed-4z6pt-6avjj-ngym4-uvwm2-kb56h-lghvd-2izv3-fqe"),
+            ("ICVC SNS 17k", "5tzzh-kqopl-zychr-jjyoi-cpvu5-y2ai7-ggf3n-icajy-bszp6-2qenk-lae"),
+        ],
+    ),
+    (
+        "NFIDW",
+        &[
+            ("NFIDW SNS 8k", "bxnp4-ym2h2-nrdxf-l5wgq-agnjv-2dei4-26s54-welyl-wk3ry-sfr4w-qqe"),
+            ("NFIDW SNS 8k", "3rxla-jua5q-xopzh-52v2p-5ih5h-s4yvq-ro2co-gdnzg-xiznr-5ubhj-3qe"),
+            ("NFIDW SNS 6.5k", "24amu-ld2uy-2klfg-kxyhq-ahwsc-vdciv-emymv-g3khl-fsawa-7ekoq-sqe"),
+            ("NFIDW SNS 5k", "ojna7-4o6fi-f4pzd-zu5m2-m5na4-miajz-kz65q-ecmmh-34j43-cfuun-gqe"),
+            ("NFIDW SNS 2k", "v6zy5-xc6h3-oduqq-rfib4-cc4n2-vyeip-4fthl-7qjir-oke3k-ftpvv-3qe"),
+        ],
+    ),
+    (
+        "WaterNeuron",
+        &[
+            ("WaterNeuron SNS 117k", "ll6pj-3wsrz-glcqo-bhjl7-s7wsj-eaykn-sllr5-4klcj-wxgbe-jm2ei-zqe"),
+            ("WaterNeuron SNS 50k", "zvcax-7pun5-oy64t-aynez-yo2jp-ftieg-otq24-ohoxk-6nlth-64fxt-tae"),
+            ("WaterNeuron SNS 34k", "ifdcz-ditqo-tojui-ncj6w-tukjj-4ujn5-j5ibk-ksch7-abkr5-pd22f-fae"),
+            ("WaterNeuron SNS 6k", "k57i6-rqxsp-3w5kd-oictq-iokt4-uqzvz-yophv-rkfkk-bnqcd-v34ie-yae"),
+            ("WaterNeuron SNS 5k", "ujszi-xic4g-vwnse-qpaqj-ybjvs-bfosw-qnzrt-atp4z-sovvc-4i4ak-wqe"),
+            ("WaterNeuron SNS 4k", "ulvvb-xozrw-cco4f-ucqug-wuiwd-iyfu5-36fgm-fnv4x-jw3j5-ey6c4-mqe"),
+            ("WaterNeuron SNS 3k", "ml6n5-esoaq-crnni-5vg4t-xolbb-clnn2-cfaud-f5i4c-rajya-fzeaz-yqe"),
+            ("WaterNeuron SNS 3k", "k4ra3-t2hvw-g3ww3-zdtx6-pqmj3-tveyx-ajjdl-5dqsv-zygbq-zhab6-6ae"),
+        ],
+    ),
+    (
+        "Yuku AI",
+        &[
+            ("Yuku SNS 30k", "pcuxr-ctpat-c7m2c-seh76-6bcez-nc5r3-rwbhf-seyrq-ntxnf-iqerm-lae"),
+            ("Yuku SNS 6k", "hk7yv-x7zw2-t2dep-sgahc-wnffi-qszuw-4mm5e-faax3-6mqpi-qmlii-7qe"),
+            ("Yuku SNS 5k", "y7bw2-cqsrh-xehac-gbcya-366ov-k2red-g6imq-ylwrj-wjoid-gsj6v-sae"),
+            ("Yuku SNS 2k", "elag7-zzhos-zwodk-cd4uv-rzx7t-extc6-yxv6g-mq7z6-kn3jt-u56ba-bae"),
+            ("Yuku SNS 2k", "wgn2s-bv3mc-ayri6-xqnuu-5zue6-jsicj-s6mcd-zshsi-utkvg-26w2n-2ae"),
+            ("Yuku SNS 2k", "aqzai-meyuz-xpeyo-zcxc2-ydfyk-ssqmt-x7tuj-4f7tj-3xkka-zsjaq-oae"),
+            ("Yuku SNS 2k", "olskz-65jdj-vbl6r-erszi-dfe4z-i3tl5-t43x6-fg25v-h53ww-wpt34-oae"),
+        ],
+    ),
 ];
 
 pub const SPAMMERS: &[&str] = &[
@@ -469,78 +529,183 @@ pub const SPAMMERS: &[&str] = &[
     "fbf37edd638b42f52320b01cbccb91f1b46eee205af5d95b59c966c8902031d8",
 ];
 
-pub const SUSPECTS: &[(&str, &str)] = &[
-    //
-    // Bots
-    //
-    ("Bot 1", "ddc050bf2a59f2d905f0c7af45854cd4cc4e406c643c322e5fa65e83a36d97da"),
-    ("Bot 2", "4ec84f148280c743948b2f54911bbcdcbc6996169f20b52eafd03544d03453fa"),
-    ("Anvil Bot 1", "hrtp2-rijcr-qzg6l-2nsvy-mf7o7-fprmq-eu2p4-hz2in-t2zh4-akabr-4qe"),
-    ("Anvil Bot 2", "npyks-khhf5-dcgjq-jkuj2-szk7v-hkjya-urhbc-ruzvl-pwfl4-363sw-2ae"),
-    ("Anvil Bot 3", "aaevx-vrwc4-kt3ew-h6b7c-npj2q-h34h3-fpn5a-56bbs-4hj6o-b4raa-aae"),
-    //
-    // Crowdfund
-    //
-    ("Crowdfund 1", "5102ea0eb3c6896cae76823d55d54a15f807a56476876090590b6a2a7e2676f0"),
-    ("Crowdfund 2", "52a9b56f251f885d9c6dac0b70e150fe0b853878af7a247ff3115bbf66d95a4d"),
-    ("Crowdfund 3", "8ac924e2eb6ad3d5c9fd6db905716aa04d949fe1a944442844214f59cf024e53"),
-    ("Crowdfund 4", "237242bc41aa441d6813784b293fcb68dc6ff772142da5ba0ad1b4221e1b56cc"),
-    //
-    // Genesis
-    //
-    ("Genesis Whale (2000) 1", "73a3e56c7177c29c731618b1c60cfeb271c00d70ae40aba9202cdec84e977d39"),
-    ("Genesis Whale (2000) 2", "843187c470d88e1b0958840c768d7592b140e4c93a0359388cc0e69c6a653833"),
-    ("Genesis Whale (2000) 3", "5a15ff1832772182e35bc73e53cd372286ca5185beed546989485349a211b798"),
-    ("Genesis Whale (2000) 4", "8b8fff2a81588e1c095af6cb9c69acc031e8bd5e2483887aceba5872e19f2424"),
-    ("Genesis Whale (2000) 5", "f7641b665a8275f61c91cb743754ff2e6f575c68477fc351d101eb74eab7f042"),
-    ("Genesis Whale (2000) 6", "573501760b5e1654dbf24852f0045426586d96f00ffd13a212f2e9cc820c0630"),
-    ("Genesis Whale (2000) 7", "eefb4d05d68c147f596d9718c7336b08b0bbbd4f2d5be692b7072904b4c1fd1a"),
-    ("Genesis Whale (2000) 8", "25e4a7d6d45cf52c9ec02cf1fdf2f1118e3843a47f3f94817031c45170aa24b8"),
-    ("Genesis Whale (2000) 9", "1055f803a4c8e19fa863c1933281b778732ffaa50b72e0e7bc8d2db25ed57ee4"),
-    ("Genesis Whale (2000) 10", "8aeb77c9e83bd3063ee576ad97b37b893bad401d43b3a66822ae3b700a5d2085"),
-    ("Genesis Whale 1", "5257f7dc8da3ab4850f4d299b5ca34f29b89f149a834099d0bd9fecab27a537d"),
-    ("Genesis Whale (10501) 1", "8ef1325bc363e8ee2d73079cf9bcd56bc0991f72715f8b229b248ba3133a0782"),
-    ("Genesis Whale (10501) 2", "06ccfd22a47cf0f0b149806bf551e5646f896f07e228d44724ea88563191d8d5"),
-    ("Genesis Whale (10501) 3", "89a1b4f7ebb8dc35b6b830b9fd48a6163fa5e04eba5747d760e9ea596ee24d71"),
-    ("Genesis Whale (10501) 4", "f42ef05c1c99e40dc01a08b5a27a6277c2bce74ad498f322c6b6cabd7ec54627"),
-    ("Genesis Whale (10501) 5", "3f8de2ecb6c011ec265aec0ce9a23abf0278c07d0471d24e956f704fe0e63118"),
-    ("Genesis Whale (10501) 1", "a4d4c3b7847ffd3188d659b85fc29836dc98bb183f9482225f6254634c4fb770"),
-    ("Genesis Mixer 1", "05ad474665f1eec0714c1a4ec941c3a395c703e14bb43100bd946d80b87828af"),
-    //
-    // Hackers
-    //
-    ("BIL Hacker", "3axar-twhdo-biizl-yegt2-fatxq-go2ay-ib5ki-y6cmq-ziiav-vcn5x-mae"),
-    ("BIL Hacker ckBTC Account", "az453-x2sxf-wewfl-pszbd-4u4rh-yq7nk-hxkrp-6yvo3-mnlce-zjvsg-qae"),
-    ("ufwij", "ufwij-jggzv-owfkb-cs26m-p7j3y-awpqg-3oa33-x4ciu-vadlo-2jb7f-gae"),
-    ("dwx4w", "dwx4w-plydf-jxgs5-uncbu-mfyds-5vjzm-oohax-gmvja-cypv7-tmbt4-dqe"),
-    //
-    // Neuron Fund
-    //
-    ("NF 1 (1.3m ICP)", "lsyd6-e7avj-lnf7q-fqga7-nb3x4-gum2h-fajff-4urd5-gve2l-tppm2-7ae"),
-    ("NF 2 (1.1m ICP)", "yjjc4-kc4ge-io5mm-m5kye-pcm2v-qwgci-yn7zh-tyj6w-ur33e-ncsmx-xae"),
-    ("NF 3 (796k ICP)", "bqjsc-ygbpe-gtqrs-nq3mf-d4iot-n2m7r-cfld2-iynvs-ls5qf-ffu2w-vqe"),
-    ("NF 4 (771k ICP)", "hrpgd-p2dys-gd5tb-krk4d-nswtt-un5h3-x6btw-j4sdm-wvscw-o2yej-iqe"),
-    ("NF 5 (223k ICP)", "4vnki-cqaaa-aaaaa-aaaaa-aaaaa-aaaaa-aaaaa-aaaaa-aaaaa-aaaaa-aae"),
-    ("NF 6 (44k ICP)", "rdwk2-noc2n-qaxh6-3alc4-uvhgt-dupge-kkoq3-v3brf-6afky-mui7j-lqe"),
-    ("NF 7 (1845 ICP)", "afxjy-xzged-ttm2u-5rjp7-exday-s6uly-ea4pc-xkiok-tjzva-23isp-vae"),
-    ("NF 8 (986 ICP)", "byfqe-a6vvd-vxehg-k5hi3-ij3v3-7n6qv-smmxm-v3vg7-mye6g-thgrs-kae"),
-    ("NF 9 (660 ICP)", "c4dgi-zb67y-vgmq3-gpm55-szzjo-mc3kt-jjov3-yytoy-ltq6t-ptyyv-lqe"),
-    ("NF 10 (572 ICP)", "amatj-baend-pdd4b-tantp-b3heu-uvusn-abmj5-hkhf2-xlvfm-jy6xp-uae"),
-    ("NF 11 (557 ICP)", "etynm-5engo-23sxo-jlss2-7jnkl-zxqv2-3s3s7-w7kpt-uaqnb-ckg6m-rae"),
-    ("NF 12 (411 ICP)", "bgmtq-s5ra3-l4ftn-zmi5f-wg2o4-zolb4-pyyez-hyttd-7rvuw-r3gyl-4ae"),
-    ("NF 13 (396 ICP)", "oggca-p5idg-tq22l-meqsr-kupbo-m3lpf-h6wi7-zplva-coxgr-tm3vt-2qe"),
-    //
-    // Odd
-    //
-    ("Approver 1", "6202e0cfffbbb22acd373aba740d2c10d84a1c6b044b97fe4f649c9c7a2426b6"),
-    ("Burner", "78384208af4e63ff27ec3ea532b1d7ccbadcbad859943267d3296aef2361b6b7"),
-    //
-    // Scams
-    //
-    ("CigDAO", "onxlw-tiaaa-aaaan-qedoq-cai"),
-    ("CLOWN Rugger", "ubojc-qnw5m-ty4f7-svlu2-hrkqo-ctqld-5jv75-222sn-ezjla-lamyt-xae"),
-    ("FomoWell/ICPEx Bitget Wallet", "f0aa2c07a00e46e1f68199fd985e3db919940454a75d49d443bbb34bdefa3442"),
-    ("Yuku MEXC Wallet", "fa5112a4d94b725aee705f1a8c65021fe69142e6717e60a9daa98f5d8218bd0f"),
-    ("Yuku Binance Wallet", "2d6a4470704440c1c3baacdfa9c8bee9fc6e3ae9aa665dfc4943157ca69cac38"),
-    ("Yuku Scam 'The Key'", "hixho-gysjl-vlky6-tjf2u-xb7nx-rgjfx-h32gc-nvsy3-mio64-4amgy-mqe"),
+// grouped by subcategory
+pub const SUSPECTS: &[(&str, &[(&str, &str)])] = &[
+    (
+        "Bots",
+        &[
+            ("Bot 1", "ddc050bf2a59f2d905f0c7af45854cd4cc4e406c643c322e5fa65e83a36d97da"),
+            ("Bot 2", "4ec84f148280c743948b2f54911bbcdcbc6996169f20b52eafd03544d03453fa"),
+            ("Anvil Bot 1", "hrtp2-rijcr-qzg6l-2nsvy-mf7o7-fprmq-eu2p4-hz2in-t2zh4-akabr-4qe"),
+            ("Anvil Bot 2", "npyks-khhf5-dcgjq-jkuj2-szk7v-hkjya-urhbc-ruzvl-pwfl4-363sw-2ae"),
+            ("Anvil Bot 3", "aaevx-vrwc4-kt3ew-h6b7c-npj2q-h34h3-fpn5a-56bbs-4hj6o-b4raa-aae"),
+        ],
+    ),
+    (
+        "Crowdfund",
+        &[
+            ("Crowdfund 1", "5102ea0eb3c6896cae76823d55d54a15f807a56476876090590b6a2a7e2676f0"),
+            ("Crowdfund 2", "52a9b56f251f885d9c6dac0b70e150fe0b853878af7a247ff3115bbf66d95a4d"),
+            ("Crowdfund 3", "8ac924e2eb6ad3d5c9fd6db905716aa04d949fe1a944442844214f59cf024e53"),
+            ("Crowdfund 4", "237242bc41aa441d6813784b293fcb68dc6ff772142da5ba0ad1b4221e1b56cc"),
+        ],
+    ),
+    (
+        "Genesis",
+        &[
+            ("Genesis Whale (2000) 1", "73a3e56c7177c29c731618b1c60cfeb271c00d70ae40aba9202cdec84e977d39"),
+            ("Genesis Whale (2000) 2", "843187c470d88e1b0958840c768d7592b140e4c93a0359388cc0e69c6a653833"),
+            ("Genesis Whale (2000) 3", "5a15ff1832772182e35bc73e53cd372286ca5185beed546989485349a211b798"),
+            ("Genesis Whale (2000) 4", "8b8fff2a81588e1c095af6cb9c69acc031e8bd5e2483887aceba5872e19f2424"),
+            ("Genesis Whale (2000) 5", "f7641b665a8275f61c91cb743754ff2e6f575c68477fc351d101eb74eab7f042"),
+            ("Genesis Whale (2000) 6", "573501760b5e1654dbf24852f0045426586d96f00ffd13a212f2e9cc820c0630"),
+            ("Genesis Whale (2000) 7", "eefb4d05d68c147f596d9718c7336b08b0bbbd4f2d5be692b7072904b4c1fd1a"),
+            ("Genesis Whale (2000) 8", "25e4a7d6d45cf52c9ec02cf1fdf2f1118e3843a47f3f94817031c45170aa24b8"),
+            ("Genesis Whale (2000) 9", "1055f803a4c8e19fa863c1933281b778732ffaa50b72e0e7bc8d2db25ed57ee4"),
+            ("Genesis Whale (2000) 10", "8aeb77c9e83bd3063ee576ad97b37b893bad401d43b3a66822ae3b700a5d2085"),
+            ("Genesis Whale 1", "5257f7dc8da3ab4850f4d299b5ca34f29b89f149a834099d0bd9fecab27a537d"),
+            ("Genesis Whale (10501) 1", "8ef1325bc363e8ee2d73079cf9bcd56bc0991f72715f8b229b248ba3133a0782"),
+            ("Genesis Whale (10501) 2", "06ccfd22a47cf0f0b149806bf551e5646f896f07e228d44724ea88563191d8d5"),
+            ("Genesis Whale (10501) 3", "89a1b4f7ebb8dc35b6b830b9fd48a6163fa5e04eba5747d760e9ea596ee24d71"),
+            ("Genesis Whale (10501) 4", "f42ef05c1c99e40dc01a08b5a27a6277c2bce74ad498f322c6b6cabd7ec54627"),
+            ("Genesis Whale (10501) 5", "3f8de2ecb6c011ec265aec0ce9a23abf0278c07d0471d24e956f704fe0e63118"),
+            ("Genesis Whale (10501) 1", "a4d4c3b7847ffd3188d659b85fc29836dc98bb183f9482225f6254634c4fb770"),
+            ("Genesis Mixer 1", "05ad474665f1eec0714c1a4ec941c3a395c703e14bb43100bd946d80b87828af"),
+        ],
+    ),
+    (
+        "Hackers",
+        &[
+            ("BIL Hacker", "3axar-twhdo-biizl-yegt2-fatxq-go2ay-ib5ki-y6cmq-ziiav-vcn5x-mae"),
+            ("BIL Hacker ckBTC Account", "az453-x2sxf-wewfl-pszbd-4u4rh-yq7nk-hxkrp-6yvo3-mnlce-zjvsg-qae"),
+            ("ufwij", "ufwij-jggzv-owfkb-cs26m-p7j3y-awpqg-3oa33-x4ciu-vadlo-2jb7f-gae"),
+            ("dwx4w", "dwx4w-plydf-jxgs5-uncbu-mfyds-5vjzm-oohax-gmvja-cypv7-tmbt4-dqe"),
+        ],
+    ),
+    (
+        "Neuron Fund",
+        &[
+            ("NF 1 (1.3m ICP)", "lsyd6-e7avj-lnf7q-fqga7-nb3x4-gum2h-fajff-4urd5-gve2l-tppm2-7ae"),
+            ("NF 2 (1.1m ICP)", "yjjc4-kc4ge-io5mm-m5kye-pcm2v-qwgci-yn7zh-tyj6w-ur33e-ncsmx-xae"),
+            ("NF 3 (796k ICP)", "bqjsc-ygbpe-gtqrs-nq3mf-d4iot-n2m7r-cfld2-iynvs-ls5qf-ffu2w-vqe"),
+            ("NF 4 (771k ICP)", "hrpgd-p2dys-gd5tb-krk4d-nswtt-un5h3-x6btw-j4sdm-wvscw-o2yej-iqe"),
+            ("NF 5 (223k ICP)", "4vnki-cqaaa-aaaaa-aaaaa-aaaaa-aaaaa-aaaaa-aaaaa-aaaaa-aaaaa-aae"),
+            ("NF 6 (44k ICP)", "rdwk2-noc2n-qaxh6-3alc4-uvhgt-dupge-kkoq3-v3brf-6afky-mui7j-lqe"),
+            ("NF 7 (1845 ICP)", "afxjy-xzged-ttm2u-5rjp7-exday-s6uly-ea4pc-xkiok-tjzva-23isp-vae"),
+            ("NF 8 (986 ICP)", "byfqe-a6vvd-vxehg-k5hi3-ij3v3-7n6qv-smmxm-v3vg7-mye6g-thgrs-kae"),
+            ("NF 9 (660 ICP)", "c4dgi-zb67y-vgmq3-gpm55-szzjo-mc3kt-jjov3-yytoy-ltq6t-ptyyv-lqe"),
+            ("NF 10 (572 ICP)", "amatj-baend-pdd4b-tantp-b3heu-uvusn-abmj5-hkhf2-xlvfm-jy6xp-uae"),
+            ("NF 11 (557 ICP)", "etynm-5engo-23sxo-jlss2-7jnkl-zxqv2-3s3s7-w7kpt-uaqnb-ckg6m-rae"),
+            ("NF 12 (411 ICP)", "bgmtq-s5ra3-l4ftn-zmi5f-wg2o4-zolb4-pyyez-hyttd-7rvuw-r3gyl-4ae"),
+            ("NF 13 (396 ICP)", "oggca-p5idg-tq22l-meqsr-kupbo-m3lpf-h6wi7-zplva-coxgr-tm3vt-2qe"),
+        ],
+    ),
+    (
+        "Odd",
+        &[
+            ("Approver 1", "6202e0cfffbbb22acd373aba740d2c10d84a1c6b044b97fe4f649c9c7a2426b6"),
+            ("Burner", "78384208af4e63ff27ec3ea532b1d7ccbadcbad859943267d3296aef2361b6b7"),
+        ],
+    ),
+    (
+        "Scams",
+        &[
+            ("CigDAO", "onxlw-tiaaa-aaaan-qedoq-cai"),
+            ("CLOWN Rugger", "ubojc-qnw5m-ty4f7-svlu2-hrkqo-ctqld-5jv75-222sn-ezjla-lamyt-xae"),
+            ("FomoWell/ICPEx Bitget Wallet", "f0aa2c07a00e46e1f68199fd985e3db919940454a75d49d443bbb34bdefa3442"),
+            ("Yuku MEXC Wallet", "fa5112a4d94b725aee705f1a8c65021fe69142e6717e60a9daa98f5d8218bd0f"),
+            ("Yuku Binance Wallet", "2d6a4470704440c1c3baacdfa9c8bee9fc6e3ae9aa665dfc4943157ca69cac38"),
+            ("Yuku Scam 'The Key'", "hixho-gysjl-vlky6-tjf2u-xb7nx-rgjfx-h32gc-nvsy3-mio64-4amgy-mqe"),
+        ],
+    ),
+];
+
+// entities owning several address book names, every other name is its own entity
+pub const ENTITIES: &[(&str, &[&str])] = &[
+    ("Binance", &["Binance 1", "Binance 2", "Binance 3"]),
+    (
+        "Coinbase",
+        &[
+            "Coinbase 1",
+            "Coinbase 2",
+            "Coinbase 3",
+            "Coinbase (Inactive 2021) 1",
+            "Coinbase (Inactive 2021) 2",
+            "Coinbase (Inactive 2021) 3",
+        ],
+    ),
+    ("CoinEx", &["CoinEx", "CoinEx (inactive)"]),
+    ("KuCoin", &["KuCoin 1", "KuCoin 2"]),
+    ("OKX", &["OKX 1", "OKX 2"]),
+    ("Bot", &["Bot 1", "Bot 2"]),
+    ("Anvil Bot", &["Anvil Bot 1", "Anvil Bot 2", "Anvil Bot 3"]),
+    ("Crowdfund", &["Crowdfund 1", "Crowdfund 2", "Crowdfund 3", "Crowdfund 4"]),
+    (
+        "Genesis Whale (2000)",
+        &[
+            "Genesis Whale (2000) 1",
+            "Genesis Whale (2000) 2",
+            "Genesis Whale (2000) 3",
+            "Genesis Whale (2000) 4",
+            "Genesis Whale (2000) 5",
+            "Genesis Whale (2000) 6",
+            "Genesis Whale (2000) 7",
+            "Genesis Whale (2000) 8",
+            "Genesis Whale (2000) 9",
+            "Genesis Whale (2000) 10",
+        ],
+    ),
+    (
+        "Genesis Whale (10501)",
+        &[
+            "Genesis Whale (10501) 1",
+            "Genesis Whale (10501) 2",
+            "Genesis Whale (10501) 3",
+            "Genesis Whale (10501) 4",
+            "Genesis Whale (10501) 5",
+        ],
+    ),
+    (
+        "Genesis",
+        &[
+            "Genesis 1.3m 1",
+            "Genesis 1.4m 1",
+            "Genesis 107m",
+            "Genesis 4.1m",
+            "Genesis 1.3m 2",
+            "Genesis 1.3m 3",
+            "Genesis 1.4m 2",
+            "Genesis 1.3m 4",
+            "Genesis 1.2m 1",
+            "Genesis 1.3m 5",
+            "Genesis 2m",
+            "Genesis 1.2m 2",
+            "Genesis 3.8m 1",
+            "Genesis 1.3m 6",
+            "Genesis 1.3m 7",
+            "Genesis 1.2m 3",
+            "Genesis 3.8m 2",
+            "Genesis 1.4m 3",
+            "Genesis 1.5m 1",
+            "Genesis 1.5m 2",
+            "Genesis 1.2m 4",
+            "Genesis 3.5m",
+            "Genesis 1.2m 5",
+            "Genesis 1.3m 8",
+            "Genesis 1.3m 9",
+            "Genesis 1.4m 4",
+        ],
+    ),
+];
+
+// free-form tags by address, on top of the subcategories above
+pub const TAGS: &[(&str, &[&str])] = &[
+    ("c50accaa515fe677f04d6a608d306dce10ed0d46048aa5105cb549256f3c4433", &["inactive"]),
+    ("a6ed987d89796f921c8a49d275ec7c9aa04e75a8fc8cd2dbaa5da799f0215ab0", &["inactive", "inactive-2021"]),
+    ("660b1680dafeedaa68c1f1f4cf8af42ed1dfb8564646efe935a2b9a48528b605", &["inactive", "inactive-2021"]),
+    ("4878d23a09b554157b31323004e1cc053567671426ca4eec7b7e835db607b965", &["inactive", "inactive-2021"]),
+    ("az453-x2sxf-wewfl-pszbd-4u4rh-yq7nk-hxkrp-6yvo3-mnlce-zjvsg-qae", &["ckbtc"]),
+    ("fa5112a4d94b725aee705f1a8c65021fe69142e6717e60a9daa98f5d8218bd0f", &["yuku"]),
+    ("2d6a4470704440c1c3baacdfa9c8bee9fc6e3ae9aa665dfc4943157ca69cac38", &["yuku"]),
+    ("hixho-gysjl-vlky6-tjf2u-xb7nx-rgjfx-h32gc-nvsy3-mio64-4amgy-mqe", &["yuku"]),
 ];
@@ -0,0 +1,106 @@
+use candid::Principal;
+use ic_agent::Agent;
+use sha2::{Digest, Sha256};
+use std::{
+    error::Error,
+    future::Future,
+    path::{Path, PathBuf},
+};
+
+///
+/// CanisterBackend
+///
+/// Where raw candid query responses come from: the IC through an agent, or
+/// fixture files so that the logic on top can run offline.
+///
+pub trait CanisterBackend {
+    fn query_bytes(
+        &self,
+        canister_id: Principal,
+        method: &str,
+        arg: Vec<u8>,
+    ) -> impl Future<Output = Result<Vec<u8>, Box<dyn Error>>>;
+}
+
+impl CanisterBackend for Agent {
+    async fn query_bytes(&self, canister_id: Principal, method: &str, arg: Vec<u8>) -> Result<Vec<u8>, Box<dyn Error>> {
+        Ok(self.query(&canister_id, method).with_arg(arg).call().await?)
+    }
+}
+
+///
+/// FixtureBackend
+///
+/// Answers queries from files written by RecordingBackend.
+///
+pub struct FixtureBackend {
+    pub dir: PathBuf,
+}
+
+impl CanisterBackend for FixtureBackend {
+    async fn query_bytes(&self, canister_id: Principal, method: &str, arg: Vec<u8>) -> Result<Vec<u8>, Box<dyn Error>> {
+        let path = fixture_path(&self.dir, canister_id, method, &arg);
+        std::fs::read(&path)
+            .map_err(|e| format!("no fixture {} for {canister_id} {method}: {e}", path.display()).into())
+    }
+}
+
+///
+/// RecordingBackend
+///
+/// Passes queries through and saves every response as a fixture.
+///
+pub struct RecordingBackend<'a, B> {
+    pub inner: &'a B,
+    pub dir: PathBuf,
+}
+
+impl<B: CanisterBackend> CanisterBackend for RecordingBackend<'_, B> {
+    async fn query_bytes(&self, canister_id: Principal, method: &str, arg: Vec<u8>) -> Result<Vec<u8>, Box<dyn Error>> {
+        let path = fixture_path(&self.dir, canister_id, method, &arg);
+        let response = self.inner.query_bytes(canister_id, method, arg).await?;
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&path, &response)?;
+
+        Ok(response)
+    }
+}
+
+// fixture_path
+// <dir>/<canister>/<method>-<first 8 bytes of sha256(arg) in hex>.bin
+fn fixture_path(dir: &Path, canister_id: Principal, method: &str, arg: &[u8]) -> PathBuf {
+    let digest = Sha256::digest(arg);
+    dir.join(canister_id.to_text()).join(format!("{method}-{}.bin", hex::encode(&digest[..8])))
+}
+
+///
+/// MemoryBackend
+///
+/// Answers queries from responses registered in memory, for tests.
+///
+#[cfg(test)]
+#[derive(Default)]
+pub struct MemoryBackend {
+    responses: std::collections::HashMap<(Principal, String, Vec<u8>), Vec<u8>>,
+}
+
+#[cfg(test)]
+impl MemoryBackend {
+    // respond
+    // the encoded response to `method` called on `canister_id` with the encoded arg
+    pub fn respond(&mut self, canister_id: Principal, method: &str, arg: Vec<u8>, response: Vec<u8>) {
+        self.responses.insert((canister_id, method.to_string(), arg), response);
+    }
+}
+
+#[cfg(test)]
+impl CanisterBackend for MemoryBackend {
+    async fn query_bytes(&self, canister_id: Principal, method: &str, arg: Vec<u8>) -> Result<Vec<u8>, Box<dyn Error>> {
+        self.responses
+            .get(&(canister_id, method.to_string(), arg))
+            .cloned()
+            .ok_or_else(|| format!("no response for {canister_id} {method}").into())
+    }
+}
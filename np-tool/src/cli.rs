@@ -0,0 +1,290 @@
+use crate::{
+    entities::ExportLevel, export_filter::SpamMode, helper::parse_timestamp, ledger::TransactionSource,
+    spam::SpamOptions, transactions::DateRangeFilter, Type,
+};
+use candid::Principal;
+use clap::{Parser, Subcommand};
+use std::path::PathBuf;
+
+///
+/// Args
+///
+
+#[derive(Debug, Parser)]
+#[command(name = "np-tool", about = "Fetch node provider and labelled account data from the IC")]
+pub struct Args {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
+    /// Only include rewards and transactions from this point on (YYYY-MM-DD or unix seconds)
+    #[arg(long, global = true, value_parser = parse_since)]
+    pub since: Option<u64>,
+
+    /// Only include rewards and transactions up to this point (YYYY-MM-DD inclusive, or unix seconds)
+    #[arg(long, global = true, value_parser = parse_until)]
+    pub until: Option<u64>,
+
+    /// Export only the counterparty summary for accounts with more transfers than this
+    #[arg(long)]
+    pub max_transfers: Option<usize>,
+
+    /// Also write the transfer graph as GraphML (yEd, Gephi)
+    #[arg(long, value_name = "PATH")]
+    pub graphml: Option<PathBuf>,
+
+    /// Also write the transfer graph as GEXF (Gephi)
+    #[arg(long, value_name = "PATH")]
+    pub gexf: Option<PathBuf>,
+
+    /// Also write the transfer graph as DOT (Graphviz)
+    #[arg(long, value_name = "PATH")]
+    pub dot: Option<PathBuf>,
+
+    /// Also write transactions.csv and entities.csv into this directory
+    #[arg(long, value_name = "DIR")]
+    pub csv: Option<PathBuf>,
+
+    /// Also write everything into a SQLite database, replacing the file if it exists
+    #[arg(long, value_name = "PATH")]
+    pub sqlite: Option<PathBuf>,
+
+    /// Also write unlabelled accounts shared by node providers and suspects, grouped for review
+    #[arg(long, value_name = "PATH")]
+    pub clusters: Option<PathBuf>,
+
+    /// Also write node provider pairs ranked by evidence of common ownership
+    #[arg(long, value_name = "PATH")]
+    pub ownership: Option<PathBuf>,
+
+    /// Also write the SNS swap participation claimed by SNS participant names, with totals per SNS
+    #[arg(long, value_name = "PATH")]
+    pub sns_participation: Option<PathBuf>,
+
+    /// Also write accounts that look like dust spammers but are not in SPAMMERS
+    #[arg(long, value_name = "PATH")]
+    pub spam_candidates: Option<PathBuf>,
+
+    /// Transfers of at most this many e8s count as dust
+    #[arg(long, default_value_t = 100_000)]
+    pub spam_max_amount_e8s: u64,
+
+    /// Dust transfers an account must send to be a spam candidate
+    #[arg(long, default_value_t = 20)]
+    pub spam_min_transfers: u64,
+
+    /// Distinct dust recipients an account must have to be a spam candidate
+    #[arg(long, default_value_t = 10)]
+    pub spam_min_recipients: usize,
+
+    /// Drop transfers below this many e8s from every export
+    #[arg(long, default_value_t = 0)]
+    pub min_amount_e8s: u64,
+
+    /// Keep, drop, or collapse into one aggregate the transfers with known spammers
+    #[arg(long, value_enum, default_value_t = SpamMode::Keep)]
+    pub spam_transfers: SpamMode,
+
+    /// Only fetch and export address book entries with this tag or subcategory (repeatable)
+    #[arg(long, value_name = "TAG")]
+    pub tag: Vec<String>,
+
+    /// Export one record per address book entry, or roll them up into entities
+    #[arg(long, value_enum, default_value_t = ExportLevel::Address)]
+    pub level: ExportLevel,
+
+    /// Leave known spammers, spam candidates and their transfers out of the graph exports
+    #[arg(long)]
+    pub exclude_spam: bool,
+
+    /// Leave out the ICP treasury and swap accounts of SNSes
+    #[arg(long)]
+    pub skip_sns_accounts: bool,
+
+    /// Where account histories come from: the index canister, ledger blocks, or the ledger when the index fails
+    #[arg(long, value_enum, default_value_t = TransactionSource::Index)]
+    pub source: TransactionSource,
+
+    /// First ledger block to scan (the latest --block-count blocks when omitted)
+    #[arg(long)]
+    pub start_block: Option<u64>,
+
+    /// Number of ledger blocks to scan
+    #[arg(long, default_value_t = 100_000)]
+    pub block_count: u64,
+
+    /// Also compare transfers fetched from the index with their (uncertified) ledger blocks and write the mismatches
+    #[arg(long, value_name = "PATH")]
+    pub verify_index: Option<PathBuf>,
+
+    /// Compare only this many transfers, spread evenly over their ids, instead of all
+    #[arg(long, value_name = "N", requires = "verify_index")]
+    pub verify_sample: Option<usize>,
+}
+
+///
+/// Command
+///
+/// Without a subcommand, np-tool fetches and exports the whole address book.
+///
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Follow outgoing transfers from an account until they reach labelled accounts
+    Trace(TraceArgs),
+
+    /// Follow node provider rewards into exchange accounts
+    RewardsToExchanges(RewardsToExchangesArgs),
+
+    /// Compare SNS participation claimed by participant names with the swap canisters
+    VerifySns(VerifySnsArgs),
+
+    /// Fetch ICRC-1 token histories of labelled principals from token ledger index canisters
+    Icrc(IcrcArgs),
+}
+
+///
+/// TraceArgs
+///
+
+#[derive(Debug, clap::Args)]
+pub struct TraceArgs {
+    /// Hex account id or principal to start from
+    pub source: String,
+
+    /// Maximum number of transfers in a path
+    #[arg(long, default_value_t = 3)]
+    pub max_hops: usize,
+
+    /// Follow at most this many recipients per account, largest first
+    #[arg(long, default_value_t = 10)]
+    pub max_fan_out: usize,
+
+    /// Ignore transfers below this many e8s
+    #[arg(long, default_value_t = 0)]
+    pub min_amount_e8s: u64,
+
+    /// End paths only at labelled accounts of these types (any label when omitted)
+    #[arg(long, value_enum)]
+    pub stop_at: Vec<Type>,
+
+    /// Where to write the trace report
+    #[arg(long, default_value = "trace.json")]
+    pub output: PathBuf,
+}
+
+///
+/// RewardsToExchangesArgs
+///
+
+#[derive(Debug, clap::Args)]
+pub struct RewardsToExchangesArgs {
+    /// Maximum number of transfers between a reward account and an exchange
+    #[arg(long, default_value_t = 2)]
+    pub max_hops: usize,
+
+    /// Follow at most this many recipients per account, largest first
+    #[arg(long, default_value_t = 5)]
+    pub max_fan_out: usize,
+
+    /// Ignore transfers below this many e8s
+    #[arg(long, default_value_t = 0)]
+    pub min_amount_e8s: u64,
+
+    /// Where to write the report
+    #[arg(long, default_value = "rewards_to_exchanges.json")]
+    pub output: PathBuf,
+}
+
+///
+/// VerifySnsArgs
+///
+
+#[derive(Debug, clap::Args)]
+pub struct VerifySnsArgs {
+    /// A claim matches when it is within this percentage of the amount on chain
+    #[arg(long, default_value_t = 10.0)]
+    pub tolerance_percent: f64,
+
+    /// Answer canister queries from recorded fixtures instead of the IC
+    #[arg(long, value_name = "DIR", conflicts_with = "record")]
+    pub fixtures: Option<PathBuf>,
+
+    /// Save every canister response as a fixture
+    #[arg(long, value_name = "DIR")]
+    pub record: Option<PathBuf>,
+
+    /// Where to write the report
+    #[arg(long, default_value = "sns_verification.json")]
+    pub output: PathBuf,
+}
+
+///
+/// IcrcArgs
+///
+
+#[derive(Debug, clap::Args)]
+pub struct IcrcArgs {
+    /// Ledger and index canister as LEDGER:INDEX, or just LEDGER to read its blocks (repeatable, TOKEN_LEDGERS when omitted)
+    #[arg(long, value_name = "LEDGER[:INDEX]", value_parser = parse_ledger)]
+    pub ledger: Vec<(Principal, Option<Principal>)>,
+
+    /// Also fetch every SNS token, with the ledger and index listed by its root
+    #[arg(long)]
+    pub sns: bool,
+
+    /// Build histories from the ICRC-3 block log even when there is an index canister
+    #[arg(long)]
+    pub icrc3: bool,
+
+    /// First block to read from the block log (the latest --block-count blocks when omitted)
+    #[arg(long)]
+    pub start_block: Option<u64>,
+
+    /// Number of blocks to read from the block log
+    #[arg(long, default_value_t = 100_000)]
+    pub block_count: u64,
+
+    /// Where to write the token histories
+    #[arg(long, default_value = "icrc_transactions.json")]
+    pub output: PathBuf,
+}
+
+impl Args {
+    // date_filter
+    pub fn date_filter(&self) -> Option<DateRangeFilter> {
+        if self.since.is_none() && self.until.is_none() {
+            return None;
+        }
+
+        Some(DateRangeFilter { start_timestamp_seconds: self.since, end_timestamp_seconds: self.until })
+    }
+
+    // spam_options
+    pub fn spam_options(&self) -> SpamOptions {
+        SpamOptions {
+            max_amount_e8s: self.spam_max_amount_e8s,
+            min_transfers: self.spam_min_transfers,
+            min_recipients: self.spam_min_recipients,
+        }
+    }
+}
+
+// parse_since
+fn parse_since(input: &str) -> Result<u64, String> {
+    parse_timestamp(input, false)
+}
+
+// parse_until
+fn parse_until(input: &str) -> Result<u64, String> {
+    parse_timestamp(input, true)
+}
+
+// parse_ledger
+fn parse_ledger(input: &str) -> Result<(Principal, Option<Principal>), String> {
+    let parse = |id: &str| Principal::from_text(id).map_err(|e| format!("{id}: {e}"));
+
+    match input.split_once(':') {
+        Some((ledger, index)) => Ok((parse(ledger)?, Some(parse(index)?))),
+        None => Ok((parse(input)?, None)),
+    }
+}
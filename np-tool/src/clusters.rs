@@ -0,0 +1,166 @@
+use crate::{transactions::AccountTransactionsJson, Type};
+use serde::Serialize;
+use std::{
+    cmp::Reverse,
+    collections::{BTreeMap, BTreeSet},
+};
+
+// node provider or suspect neighbours an unlabelled account needs to be a candidate
+pub const MIN_SUSPICIOUS_NEIGHBOURS: usize = 2;
+
+///
+/// ClusterCandidate
+///
+/// An unlabelled account and the labelled entities it exchanged ICP with.
+///
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ClusterCandidate {
+    pub account: String,
+    pub neighbours: Vec<String>,
+    pub suspicious_neighbours: usize,
+    pub volume_e8s: u64,
+    pub transfer_count: u64,
+}
+
+///
+/// Cluster
+///
+/// Candidates sharing the same node provider and suspect neighbours.
+///
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Cluster {
+    pub entities: Vec<String>,
+    pub accounts: Vec<ClusterCandidate>,
+    pub volume_e8s: u64,
+}
+
+///
+/// ClusterReport
+///
+
+#[derive(Debug, Serialize)]
+pub struct ClusterReport {
+    pub candidate_count: usize,
+    pub clusters: Vec<Cluster>,
+}
+
+#[derive(Debug, Default)]
+struct Neighbourhood<'a> {
+    neighbours: BTreeSet<&'a str>,
+    suspicious: BTreeSet<&'a str>,
+    volume_e8s: u64,
+    transfer_count: u64,
+}
+
+// find_clusters
+// Unlabelled counterparties of at least MIN_SUSPICIOUS_NEIGHBOURS node provider or
+// suspect entities. Candidates rank by distinct labelled neighbours, then volume,
+// and clusters by suspicious entities, then volume. Expects counterparties to have been summarized and labelled already.
+pub fn find_clusters(results: &[AccountTransactionsJson]) -> ClusterReport {
+    let mut unknown: BTreeMap<&str, Neighbourhood> = BTreeMap::new();
+
+    for account_tx in results {
+        let suspicious = matches!(account_tx.ty, Type::NodeProvider | Type::Suspect);
+        for flow in account_tx.counterparties.iter().filter(|flow| flow.label.is_none()) {
+            let entry = unknown.entry(flow.counterparty.as_str()).or_default();
+            entry.neighbours.insert(&account_tx.name);
+            if suspicious {
+                entry.suspicious.insert(&account_tx.name);
+            }
+            entry.volume_e8s = entry.volume_e8s.saturating_add(flow.volume_e8s());
+            entry.transfer_count += flow.transfer_count;
+        }
+    }
+
+    let mut clusters: BTreeMap<Vec<&str>, Vec<ClusterCandidate>> = BTreeMap::new();
+    let mut candidate_count = 0;
+    for (account, hood) in unknown.into_iter().filter(|(_, hood)| hood.suspicious.len() >= MIN_SUSPICIOUS_NEIGHBOURS) {
+        candidate_count += 1;
+        clusters.entry(hood.suspicious.iter().copied().collect()).or_default().push(ClusterCandidate {
+            account: account.to_string(),
+            neighbours: hood.neighbours.iter().map(|name| name.to_string()).collect(),
+            suspicious_neighbours: hood.suspicious.len(),
+            volume_e8s: hood.volume_e8s,
+            transfer_count: hood.transfer_count,
+        });
+    }
+
+    let mut clusters: Vec<Cluster> = clusters
+        .into_iter()
+        .map(|(entities, mut accounts)| {
+            accounts.sort_by_key(|c| Reverse((c.neighbours.len(), c.volume_e8s)));
+            Cluster {
+                entities: entities.into_iter().map(String::from).collect(),
+                volume_e8s: accounts.iter().map(|c| c.volume_e8s).sum(),
+                accounts,
+            }
+        })
+        .collect();
+    clusters.sort_by_key(|c| Reverse((c.entities.len(), c.volume_e8s)));
+
+    ClusterReport { candidate_count, clusters }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{flows::CounterpartyFlow, AccountData};
+
+    fn flow(counterparty: &str, volume_e8s: u64) -> CounterpartyFlow {
+        CounterpartyFlow {
+            counterparty: counterparty.to_string(),
+            label: None,
+            sent_e8s: volume_e8s,
+            received_e8s: 0,
+            transfer_count: 1,
+            first_timestamp_nanos: None,
+            last_timestamp_nanos: None,
+        }
+    }
+
+    fn entity(name: &str, ty: Type, counterparties: Vec<CounterpartyFlow>) -> AccountTransactionsJson {
+        let account = hex::encode(name);
+        let mut account_tx = AccountTransactionsJson::label_only(&AccountData::new(name, &account, ty).unwrap(), None);
+        account_tx.counterparties = counterparties;
+        account_tx
+    }
+
+    fn accounts(cluster: &Cluster) -> Vec<&str> {
+        cluster.accounts.iter().map(|c| c.account.as_str()).collect()
+    }
+
+    #[test]
+    fn candidates_need_enough_suspicious_neighbours() {
+        let report = find_clusters(&[
+            entity("Alpha", Type::NodeProvider, vec![flow("xx", 100), flow("yy", 100)]),
+            entity("Bravo", Type::Suspect, vec![flow("xx", 100)]),
+            // a Cex neighbour is not suspicious
+            entity("Charlie", Type::Cex, vec![flow("yy", 100)]),
+        ]);
+
+        assert_eq!(report.candidate_count, 1);
+        assert_eq!(report.clusters.len(), 1);
+        assert_eq!(report.clusters[0].entities, ["Alpha", "Bravo"]);
+        assert_eq!(accounts(&report.clusters[0]), ["xx"]);
+    }
+
+    #[test]
+    fn candidates_group_by_suspicious_set_and_rank_by_neighbours_then_volume() {
+        let report = find_clusters(&[
+            entity("Alpha", Type::NodeProvider, vec![flow("xx", 10), flow("yy", 500), flow("zz", 1), flow("ww", 1)]),
+            entity("Bravo", Type::NodeProvider, vec![flow("xx", 10), flow("yy", 500), flow("zz", 1), flow("ww", 1)]),
+            // a third labelled neighbour outranks any volume
+            entity("Charlie", Type::Identified, vec![flow("xx", 10)]),
+            entity("Delta", Type::NodeProvider, vec![flow("ww", 1)]),
+        ]);
+
+        assert_eq!(report.candidate_count, 4);
+        let [first, second] = &report.clusters[..] else { panic!("expected two clusters") };
+        assert_eq!(first.entities, ["Alpha", "Bravo", "Delta"]);
+        assert_eq!((accounts(first), first.volume_e8s), (vec!["ww"], 3));
+        assert_eq!(second.entities, ["Alpha", "Bravo"]);
+        assert_eq!((accounts(second), second.volume_e8s), (vec!["xx", "yy", "zz"], 1032));
+    }
+}
@@ -0,0 +1,173 @@
+use crate::{
+    helper::format_timestamp,
+    transactions::{AccountTransactionsJson, SimplifiedTransfer},
+    Type,
+};
+use serde::Serialize;
+use std::{io, path::Path};
+
+///
+/// TransactionRow
+///
+
+#[derive(Debug, Serialize)]
+struct TransactionRow<'a> {
+    account: Option<&'a str>,
+    account_name: &'a str,
+    account_type: Type,
+    counterparty: &'a str,
+    counterparty_name: Option<&'a str>,
+    counterparty_type: Option<Type>,
+    direction: &'static str,
+    op_type: &'a str,
+    amount_e8s: u64,
+    fee_e8s: u64,
+    timestamp: Option<String>,
+    timestamp_nanos: Option<u64>,
+    memo: u64,
+    id: u64,
+    from_label: Option<&'a str>,
+    to_label: Option<&'a str>,
+}
+
+///
+/// EntityRow
+///
+
+#[derive(Debug, Serialize)]
+struct EntityRow<'a> {
+    name: &'a str,
+    entity: Option<&'a str>,
+    #[serde(rename = "type")]
+    ty: Type,
+    subcategory: Option<&'a str>,
+    tags: String,
+    principal: Option<&'a str>,
+    account: Option<&'a str>,
+    extra_accounts: String,
+    balance_e8s: Option<u64>,
+    genesis_icp: Option<u64>,
+}
+
+// write_csv_dir
+// writes transactions.csv and entities.csv into `dir`
+pub fn write_csv_dir(dir: &Path, data: &[AccountTransactionsJson]) -> Result<(), Box<dyn std::error::Error>> {
+    std::fs::create_dir_all(dir)?;
+    write_transactions(std::fs::File::create(dir.join("transactions.csv"))?, data)?;
+    write_entities(std::fs::File::create(dir.join("entities.csv"))?, data)?;
+
+    Ok(())
+}
+
+// write_transactions
+// one row per transfer per exported account, so transfers between two
+// exported accounts appear once from each side
+pub fn write_transactions<W: io::Write>(writer: W, data: &[AccountTransactionsJson]) -> Result<(), csv::Error> {
+    let mut wtr = csv::Writer::from_writer(writer);
+
+    for account_tx in data {
+        for tx in &account_tx.transactions {
+            let (direction, counterparty) = direction(account_tx, tx);
+            let counterparty_label = tx.counterparty_label.as_ref();
+
+            wtr.serialize(TransactionRow {
+                account: account_tx.account.as_deref(),
+                account_name: &account_tx.name,
+                account_type: account_tx.ty,
+                counterparty,
+                counterparty_name: counterparty_label.map(|l| l.name.as_str()),
+                counterparty_type: counterparty_label.map(|l| l.ty),
+                direction,
+                op_type: &tx.op_type,
+                amount_e8s: tx.amount_e8s,
+                fee_e8s: tx.fee_e8s,
+                timestamp: tx.timestamp_nanos.map(|ts| format_timestamp(ts / 1_000_000_000)),
+                timestamp_nanos: tx.timestamp_nanos,
+                memo: tx.memo,
+                id: tx.id,
+                from_label: tx.from_label.as_ref().map(|l| l.name.as_str()),
+                to_label: tx.to_label.as_ref().map(|l| l.name.as_str()),
+            })?;
+        }
+    }
+
+    wtr.flush()?;
+    Ok(())
+}
+
+// write_entities
+// extra accounts are joined with ';'
+pub fn write_entities<W: io::Write>(writer: W, data: &[AccountTransactionsJson]) -> Result<(), csv::Error> {
+    let mut wtr = csv::Writer::from_writer(writer);
+
+    for account_tx in data {
+        wtr.serialize(EntityRow {
+            name: &account_tx.name,
+            entity: account_tx.entity.as_deref(),
+            ty: account_tx.ty,
+            subcategory: account_tx.subcategory.as_deref(),
+            tags: account_tx.tags.join(";"),
+            principal: account_tx.principal.as_deref(),
+            account: account_tx.account.as_deref(),
+            extra_accounts: account_tx.extra_accounts.join(";"),
+            balance_e8s: account_tx.balance_e8s,
+            genesis_icp: account_tx.genesis_icp,
+        })?;
+    }
+
+    wtr.flush()?;
+    Ok(())
+}
+
+// direction
+fn direction<'a>(account_tx: &AccountTransactionsJson, tx: &'a SimplifiedTransfer) -> (&'static str, &'a str) {
+    match (account_tx.owns(&tx.from), account_tx.owns(&tx.to)) {
+        (true, true) => ("SELF", &tx.to),
+        (true, false) => ("SEND", &tx.to),
+        _ => ("RECEIVE", &tx.from),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{labels::Label, AccountData};
+
+    #[test]
+    fn write_csv_dir_writes_both_files() {
+        let mut account_tx =
+            AccountTransactionsJson::label_only(&AccountData::new("Alpha", "aa", Type::Identified).unwrap(), None);
+        account_tx.extra_accounts = vec!["ab".to_string(), "ac".to_string()];
+        account_tx.balance_e8s = Some(900);
+        account_tx.transactions = vec![SimplifiedTransfer {
+            memo: 7,
+            counterparty_label: Some(Label { name: "Bravo".to_string(), ty: Type::Cex }),
+            to_label: Some(Label { name: "Bravo".to_string(), ty: Type::Cex }),
+            ..SimplifiedTransfer::transfer(3, "ab", "bb", 100)
+        }];
+        let dir = std::env::temp_dir().join(format!("np-tool-csv-{}", std::process::id()));
+
+        write_csv_dir(&dir, &[account_tx]).unwrap();
+        let transactions = std::fs::read_to_string(dir.join("transactions.csv")).unwrap();
+        let entities = std::fs::read_to_string(dir.join("entities.csv")).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        let transactions: Vec<&str> = transactions.lines().collect();
+        assert_eq!(
+            transactions,
+            [
+                "account,account_name,account_type,counterparty,counterparty_name,counterparty_type,direction,op_type,\
+                 amount_e8s,fee_e8s,timestamp,timestamp_nanos,memo,id,from_label,to_label",
+                "aa,Alpha,Identified,bb,Bravo,Cex,SEND,Transfer,100,10000,,,7,3,,Bravo",
+            ]
+        );
+        let entities: Vec<&str> = entities.lines().collect();
+        assert_eq!(
+            entities,
+            [
+                "name,entity,type,subcategory,tags,principal,account,extra_accounts,balance_e8s,genesis_icp",
+                "Alpha,Alpha,Identified,,,,aa,ab;ac,900,",
+            ]
+        );
+    }
+}
@@ -0,0 +1,221 @@
+use crate::{addresses::ENTITIES, export_filter::SpamSummary, transactions::AccountTransactionsJson, Type};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+///
+/// ExportLevel
+///
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum ExportLevel {
+    /// One record per address book entry
+    #[default]
+    Address,
+    /// One record per entity, with its addresses listed
+    Entity,
+}
+
+///
+/// EntityAddress
+///
+/// One address book entry rolled up into an entity.
+///
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EntityAddress {
+    pub name: String,
+    pub principal: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub subaccount: Option<String>,
+    pub account: Option<String>,
+    pub extra_accounts: Vec<String>,
+    pub transfer_count: usize,
+    pub balance_e8s: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub genesis_icp: Option<u64>,
+}
+
+impl EntityAddress {
+    fn from_account(account_tx: &AccountTransactionsJson) -> Self {
+        Self {
+            name: account_tx.name.clone(),
+            principal: account_tx.principal.clone(),
+            subaccount: account_tx.subaccount.clone(),
+            account: account_tx.account.clone(),
+            extra_accounts: account_tx.extra_accounts.clone(),
+            transfer_count: account_tx.transactions.len(),
+            balance_e8s: account_tx.balance_e8s,
+            genesis_icp: account_tx.genesis_icp,
+        }
+    }
+}
+
+// entity_name
+// the entity ENTITIES lists the name under, or the name itself
+pub fn entity_name(name: &str) -> String {
+    ENTITIES.iter().find(|(_, names)| names.contains(&name)).map_or(name, |(entity, _)| entity).to_string()
+}
+
+// roll_up
+// Merges the records of each entity, keyed by entity name and type, keeping
+// the order in which entities first appear. The first address becomes the main
+// account and every other address an extra account. Counterparties are cleared,
+// re-annotate and summarize afterwards.
+pub fn roll_up(results: Vec<AccountTransactionsJson>) -> Vec<AccountTransactionsJson> {
+    let mut index: HashMap<(String, Type), usize> = HashMap::new();
+    let mut groups: Vec<Vec<AccountTransactionsJson>> = Vec::new();
+
+    for account_tx in results {
+        let key = (entity_name(&account_tx.name), account_tx.ty);
+        let i = *index.entry(key).or_insert_with(|| {
+            groups.push(Vec::new());
+            groups.len() - 1
+        });
+        groups[i].push(account_tx);
+    }
+
+    groups.into_iter().map(merge).collect()
+}
+
+fn merge(members: Vec<AccountTransactionsJson>) -> AccountTransactionsJson {
+    let addresses: Vec<EntityAddress> = members.iter().map(EntityAddress::from_account).collect();
+    let mut members = members.into_iter();
+    let mut entity = members.next().expect("groups are never empty");
+    entity.name = entity_name(&entity.name);
+
+    let mut seen_ids: HashSet<u64> = entity.transactions.iter().map(|tx| tx.id).collect();
+    for member in members {
+        match (&entity.account, member.account) {
+            (None, account) => entity.account = account,
+            (Some(_), Some(account)) => entity.extra_accounts.push(account),
+            (Some(_), None) => {}
+        }
+        entity.extra_accounts.extend(member.extra_accounts);
+        entity.tags.extend(member.tags);
+
+        // transfers between two addresses of the entity show up in both
+        entity.transactions.extend(member.transactions.into_iter().filter(|tx| seen_ids.insert(tx.id)));
+        entity.oldest_tx_id = match (entity.oldest_tx_id, member.oldest_tx_id) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        };
+        entity.balance_e8s = match (entity.balance_e8s, member.balance_e8s) {
+            (Some(a), Some(b)) => Some(a.saturating_add(b)),
+            (a, b) => a.or(b),
+        };
+        entity.genesis_icp = match (entity.genesis_icp, member.genesis_icp) {
+            (Some(a), Some(b)) => Some(a.saturating_add(b)),
+            (a, b) => a.or(b),
+        };
+        entity.spam = merge_spam(entity.spam.take(), member.spam);
+        entity.valuation = entity.valuation.take().or(member.valuation);
+    }
+
+    entity.extra_accounts.sort();
+    entity.extra_accounts.dedup();
+    entity.tags.sort();
+    entity.tags.dedup();
+    entity.transactions.sort_by_key(|tx| std::cmp::Reverse(tx.id));
+    entity.counterparties.clear();
+    entity.addresses = addresses;
+
+    entity
+}
+
+// merge_spam
+// a spammer hitting several addresses of the entity is counted once
+fn merge_spam(a: Option<SpamSummary>, b: Option<SpamSummary>) -> Option<SpamSummary> {
+    match (a, b) {
+        (Some(a), Some(b)) => {
+            let spammers: BTreeSet<String> = a.spammers.into_iter().chain(b.spammers).collect();
+            Some(SpamSummary {
+                sent_count: a.sent_count + b.sent_count,
+                received_count: a.received_count + b.received_count,
+                sent_e8s: a.sent_e8s.saturating_add(b.sent_e8s),
+                received_e8s: a.received_e8s.saturating_add(b.received_e8s),
+                spammer_count: spammers.len(),
+                spammers,
+            })
+        }
+        (a, b) => a.or(b),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{transactions::SimplifiedTransfer, AccountData};
+
+    fn member(name: &str, account: &str, ty: Type, extra_accounts: &[&str]) -> AccountTransactionsJson {
+        let mut account_tx = AccountTransactionsJson::label_only(&AccountData::new(name, account, ty).unwrap(), None);
+        account_tx.extra_accounts = extra_accounts.iter().map(|account| account.to_string()).collect();
+        account_tx.balance_e8s = Some(100);
+        account_tx.genesis_icp = Some(2_000);
+        account_tx
+    }
+
+    fn spam(spammers: &[&str]) -> Option<SpamSummary> {
+        let spammers: BTreeSet<String> = spammers.iter().map(|spammer| spammer.to_string()).collect();
+        Some(SpamSummary { received_count: 1, spammer_count: spammers.len(), spammers, ..Default::default() })
+    }
+
+    #[test]
+    fn entity_name_follows_the_mapping() {
+        assert_eq!(entity_name("Binance 2"), "Binance");
+        assert_eq!(entity_name("Coinbase (Inactive 2021) 3"), "Coinbase");
+        assert_eq!(entity_name("Genesis Whale (2000) 10"), "Genesis Whale (2000)");
+        assert_eq!(entity_name("Genesis 1.3m 2"), "Genesis");
+    }
+
+    #[test]
+    fn unmapped_names_are_their_own_entity() {
+        assert_eq!(entity_name("Unknown 1"), "Unknown 1");
+        assert_eq!(entity_name("Bitget"), "Bitget");
+        assert_eq!(entity_name("NF 10 (572 ICP)"), "NF 10 (572 ICP)");
+    }
+
+    #[test]
+    fn every_mapped_name_is_in_the_address_book() {
+        let names: HashSet<String> = crate::get_entries().into_iter().map(|entry| entry.name).collect();
+
+        for (entity, members) in ENTITIES {
+            for name in *members {
+                assert!(names.contains(*name), "{entity} lists {name}, which is not in the address book");
+            }
+        }
+    }
+
+    #[test]
+    fn roll_up_merges_the_addresses_of_an_entity() {
+        let mut first = member("Binance 1", "aa", Type::Cex, &["ar"]);
+        let mut second = member("Binance 2", "bb", Type::Cex, &[]);
+        // the transfer between the two addresses is listed by both
+        first.transactions = vec![SimplifiedTransfer::transfer(1, "aa", "bb", 50)];
+        second.transactions =
+            vec![SimplifiedTransfer::transfer(1, "aa", "bb", 50), SimplifiedTransfer::transfer(2, "bb", "xx", 5)];
+        (first.spam, second.spam) = (spam(&["s1", "s2"]), spam(&["s2"]));
+
+        let rolled = roll_up(vec![first, member("Other", "cc", Type::Identified, &[]), second]);
+        let names: Vec<&str> = rolled.iter().map(|entity| entity.name.as_str()).collect();
+        assert_eq!(names, ["Binance", "Other"]);
+
+        let binance = &rolled[0];
+        assert_eq!(binance.account.as_deref(), Some("aa"));
+        assert_eq!(binance.extra_accounts, ["ar", "bb"]);
+        assert_eq!(binance.transactions.iter().map(|tx| tx.id).collect::<Vec<_>>(), [2, 1]);
+        assert_eq!((binance.balance_e8s, binance.genesis_icp), (Some(200), Some(4_000)));
+        assert_eq!(binance.addresses.len(), 2);
+        let spam = binance.spam.as_ref().unwrap();
+        assert_eq!((spam.received_count, spam.spammer_count), (2, 2));
+    }
+
+    #[test]
+    fn roll_up_keeps_types_apart() {
+        let rolled =
+            roll_up(vec![member("Binance 1", "aa", Type::Cex, &[]), member("Binance 2", "bb", Type::Identified, &[])]);
+
+        let entities: Vec<(&str, Type, Option<&str>)> =
+            rolled.iter().map(|entity| (entity.name.as_str(), entity.ty, entity.account.as_deref())).collect();
+        assert_eq!(entities, [("Binance", Type::Cex, Some("aa")), ("Binance", Type::Identified, Some("bb"))]);
+    }
+}
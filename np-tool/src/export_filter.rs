@@ -0,0 +1,138 @@
+use crate::{
+    transactions::{AccountTransactionsJson, SimplifiedTransfer},
+    Type,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+
+///
+/// SpamMode
+///
+/// What to do with transfers whose counterparty is a known spammer.
+///
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum SpamMode {
+    #[default]
+    Keep,
+    Drop,
+    Collapse,
+}
+
+///
+/// SpamSummary
+///
+/// Transfers with known spammers, collapsed into one aggregate. The spammer
+/// accounts are kept so that rolled up summaries count each spammer once.
+///
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SpamSummary {
+    pub sent_count: u64,
+    pub received_count: u64,
+    pub sent_e8s: u64,
+    pub received_e8s: u64,
+    pub spammer_count: usize,
+    #[serde(skip)]
+    pub spammers: BTreeSet<String>,
+}
+
+// filter_transfers
+// Drops or collapses transfers with known spammers, then drops whatever is left
+// below `min_amount_e8s`. Needs counterparty labels, so run it after annotating.
+pub fn filter_transfers(account_tx: &mut AccountTransactionsJson, min_amount_e8s: u64, spam_mode: SpamMode) {
+    if spam_mode != SpamMode::Keep {
+        let (spam, kept): (Vec<_>, Vec<_>) = std::mem::take(&mut account_tx.transactions)
+            .into_iter()
+            .partition(|tx| tx.counterparty_label.as_ref().is_some_and(|label| label.ty == Type::Spammer));
+        account_tx.transactions = kept;
+        if spam_mode == SpamMode::Collapse && !spam.is_empty() {
+            account_tx.spam = Some(summarize_spam(account_tx, &spam));
+        }
+    }
+
+    account_tx.transactions.retain(|tx| tx.amount_e8s >= min_amount_e8s);
+}
+
+// summarize_spam
+fn summarize_spam(account_tx: &AccountTransactionsJson, spam: &[SimplifiedTransfer]) -> SpamSummary {
+    let mut summary = SpamSummary::default();
+
+    for tx in spam {
+        if account_tx.owns(&tx.from) {
+            summary.sent_count += 1;
+            summary.sent_e8s = summary.sent_e8s.saturating_add(tx.amount_e8s);
+            summary.spammers.insert(tx.to.clone());
+        } else {
+            summary.received_count += 1;
+            summary.received_e8s = summary.received_e8s.saturating_add(tx.amount_e8s);
+            summary.spammers.insert(tx.from.clone());
+        }
+    }
+    summary.spammer_count = summary.spammers.len();
+
+    summary
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{labels::Label, AccountData};
+
+    fn transfer(id: u64, from: &str, to: &str, amount_e8s: u64, spam: bool) -> SimplifiedTransfer {
+        let label = spam.then(|| Label { name: "Spammer".to_string(), ty: Type::Spammer });
+        SimplifiedTransfer { counterparty_label: label, ..SimplifiedTransfer::transfer(id, from, to, amount_e8s) }
+    }
+
+    fn account() -> AccountTransactionsJson {
+        let mut account_tx =
+            AccountTransactionsJson::label_only(&AccountData::new("Alpha", "aa", Type::Identified).unwrap(), None);
+        account_tx.transactions = vec![
+            transfer(1, "aa", "bb", 500, false),
+            transfer(2, "aa", "bb", 5, false),
+            transfer(3, "s1", "aa", 1, true),
+            transfer(4, "s1", "aa", 2, true),
+            transfer(5, "aa", "s2", 300, true),
+        ];
+        account_tx
+    }
+
+    fn ids(account_tx: &AccountTransactionsJson) -> Vec<u64> {
+        account_tx.transactions.iter().map(|tx| tx.id).collect()
+    }
+
+    #[test]
+    fn spam_is_kept_dropped_or_collapsed() {
+        let mut kept = account();
+        filter_transfers(&mut kept, 0, SpamMode::Keep);
+        assert_eq!(ids(&kept), [1, 2, 3, 4, 5]);
+        assert!(kept.spam.is_none());
+
+        let mut dropped = account();
+        filter_transfers(&mut dropped, 0, SpamMode::Drop);
+        assert_eq!(ids(&dropped), [1, 2]);
+        assert!(dropped.spam.is_none());
+
+        let mut collapsed = account();
+        filter_transfers(&mut collapsed, 0, SpamMode::Collapse);
+        assert_eq!(ids(&collapsed), [1, 2]);
+        let spam = collapsed.spam.unwrap();
+        assert_eq!((spam.sent_count, spam.sent_e8s, spam.received_count, spam.received_e8s), (1, 300, 2, 3));
+        // s1 sent twice but is one spammer
+        assert_eq!(spam.spammer_count, 2);
+    }
+
+    #[test]
+    fn the_minimum_applies_after_collapsing() {
+        let mut account_tx = account();
+        filter_transfers(&mut account_tx, 10, SpamMode::Collapse);
+
+        assert_eq!(ids(&account_tx), [1]);
+        // spam below the minimum still counts toward the summary
+        assert_eq!(account_tx.spam.unwrap().received_e8s, 3);
+
+        let mut kept = account();
+        filter_transfers(&mut kept, 10, SpamMode::Keep);
+        assert_eq!(ids(&kept), [1, 5]);
+    }
+}
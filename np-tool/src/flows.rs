@@ -0,0 +1,128 @@
+use crate::{labels::Label, transactions::AccountTransactionsJson};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+///
+/// CounterpartyFlow
+///
+/// Everything one account exchanged with one counterparty.
+///
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CounterpartyFlow {
+    pub counterparty: String,
+    pub label: Option<Label>,
+    pub sent_e8s: u64,
+    pub received_e8s: u64,
+    pub transfer_count: u64,
+    pub first_timestamp_nanos: Option<u64>,
+    pub last_timestamp_nanos: Option<u64>,
+}
+
+impl CounterpartyFlow {
+    fn new(counterparty: &str, label: Option<Label>) -> Self {
+        Self {
+            counterparty: counterparty.to_string(),
+            label,
+            sent_e8s: 0,
+            received_e8s: 0,
+            transfer_count: 0,
+            first_timestamp_nanos: None,
+            last_timestamp_nanos: None,
+        }
+    }
+
+    // volume_e8s
+    pub fn volume_e8s(&self) -> u64 {
+        self.sent_e8s.saturating_add(self.received_e8s)
+    }
+}
+
+// summarize_counterparties
+// transfers between the account's own addresses are left out, the result is sorted by volume
+pub fn summarize_counterparties(account_tx: &AccountTransactionsJson) -> Vec<CounterpartyFlow> {
+    let mut flows: HashMap<&str, CounterpartyFlow> = HashMap::new();
+
+    for tx in &account_tx.transactions {
+        let sent = account_tx.owns(&tx.from);
+        let counterparty = if sent { &tx.to } else { &tx.from };
+        if account_tx.owns(counterparty) {
+            continue;
+        }
+
+        let flow = flows
+            .entry(counterparty.as_str())
+            .or_insert_with(|| CounterpartyFlow::new(counterparty, tx.counterparty_label.clone()));
+        if sent {
+            flow.sent_e8s = flow.sent_e8s.saturating_add(tx.amount_e8s);
+        } else {
+            flow.received_e8s = flow.received_e8s.saturating_add(tx.amount_e8s);
+        }
+        flow.transfer_count += 1;
+        if let Some(ts) = tx.timestamp_nanos {
+            flow.first_timestamp_nanos = Some(flow.first_timestamp_nanos.map_or(ts, |first| first.min(ts)));
+            flow.last_timestamp_nanos = Some(flow.last_timestamp_nanos.map_or(ts, |last| last.max(ts)));
+        }
+    }
+
+    let mut flows: Vec<CounterpartyFlow> = flows.into_values().collect();
+    flows.sort_by(|a, b| b.volume_e8s().cmp(&a.volume_e8s()).then_with(|| a.counterparty.cmp(&b.counterparty)));
+
+    flows
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{transactions::SimplifiedTransfer, AccountData, Type};
+
+    fn transfer(id: u64, from: &str, to: &str, amount_e8s: u64, timestamp_nanos: u64) -> SimplifiedTransfer {
+        SimplifiedTransfer {
+            timestamp_nanos: Some(timestamp_nanos),
+            ..SimplifiedTransfer::transfer(id, from, to, amount_e8s)
+        }
+    }
+
+    fn account(transactions: Vec<SimplifiedTransfer>) -> AccountTransactionsJson {
+        let mut account_tx =
+            AccountTransactionsJson::label_only(&AccountData::new("Alpha", "aa", Type::Identified).unwrap(), None);
+        account_tx.extra_accounts = vec!["ab".to_string()];
+        account_tx.transactions = transactions;
+        account_tx
+    }
+
+    #[test]
+    fn own_addresses_are_not_counterparties() {
+        let flows =
+            summarize_counterparties(&account(vec![transfer(1, "aa", "ab", 500, 1), transfer(2, "ab", "xx", 10, 2)]));
+
+        assert_eq!(flows.len(), 1);
+        assert_eq!((flows[0].counterparty.as_str(), flows[0].sent_e8s, flows[0].transfer_count), ("xx", 10, 1));
+    }
+
+    #[test]
+    fn flows_split_sent_and_received_and_span_their_timestamps() {
+        let flows = summarize_counterparties(&account(vec![
+            transfer(1, "aa", "xx", 100, 30),
+            transfer(2, "xx", "ab", 40, 10),
+            transfer(3, "aa", "xx", 5, 20),
+        ]));
+
+        let flow = &flows[0];
+        assert_eq!((flow.sent_e8s, flow.received_e8s, flow.transfer_count), (105, 40, 3));
+        assert_eq!((flow.first_timestamp_nanos, flow.last_timestamp_nanos), (Some(10), Some(30)));
+        assert_eq!(flow.volume_e8s(), 145);
+    }
+
+    #[test]
+    fn flows_are_ordered_by_volume_then_counterparty() {
+        let flows = summarize_counterparties(&account(vec![
+            transfer(1, "aa", "yy", 10, 1),
+            transfer(2, "zz", "aa", 50, 2),
+            transfer(3, "aa", "xx", 10, 3),
+        ]));
+        let order: Vec<&str> = flows.iter().map(|flow| flow.counterparty.as_str()).collect();
+
+        assert_eq!(order, ["zz", "xx", "yy"]);
+    }
+}
@@ -0,0 +1,433 @@
+use crate::{
+    export_filter::SpamSummary, labels::Label, neurons::NEURON_STAKE_LABEL, transactions::AccountTransactionsJson, Type,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+
+pub const CONNECTOR_GROUP: &str = "connector";
+pub const SPAM_GROUP: &str = "spam";
+pub const NEURON_STAKE_GROUP: &str = "neuron-stake";
+
+///
+/// Direction
+///
+/// Relative to the edge source.
+///
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum Direction {
+    Send,
+    Receive,
+    Both,
+}
+
+///
+/// GraphNode
+///
+/// One node per fetched entity, keyed by its main account id, plus connector
+/// nodes standing in for unlabelled accounts shared by several entities and
+/// one neuron stake node per entity that staked.
+///
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GraphNode {
+    pub id: String,
+    pub label: String,
+    pub group: String,
+    pub ty: Option<Type>,
+    pub accounts: Vec<String>,
+    #[serde(rename = "mainAccounts", default, skip_serializing_if = "Vec::is_empty")]
+    pub main_accounts: Vec<String>,
+}
+
+///
+/// GraphEdge
+///
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GraphEdge {
+    pub source: String,
+    pub target: String,
+    pub direction: Direction,
+    pub count: u64,
+    pub volume_e8s: u64,
+    #[serde(default)]
+    pub sent_count: u64,
+    #[serde(default)]
+    pub received_count: u64,
+    pub sent_e8s: u64,
+    pub received_e8s: u64,
+}
+
+impl GraphEdge {
+    fn from_flow(source: &str, target: &str, flow: &Flow) -> Self {
+        let direction = match (flow.sent_count > 0, flow.received_count > 0) {
+            (true, true) => Direction::Both,
+            (false, true) => Direction::Receive,
+            _ => Direction::Send,
+        };
+
+        Self {
+            source: source.to_string(),
+            target: target.to_string(),
+            direction,
+            count: flow.sent_count + flow.received_count,
+            volume_e8s: flow.sent_e8s.saturating_add(flow.received_e8s),
+            sent_count: flow.sent_count,
+            received_count: flow.received_count,
+            sent_e8s: flow.sent_e8s,
+            received_e8s: flow.received_e8s,
+        }
+    }
+}
+
+///
+/// Flow
+///
+/// Transfers seen from one side of an edge.
+///
+
+#[derive(Debug, Clone, Copy, Default)]
+struct Flow {
+    sent_count: u64,
+    received_count: u64,
+    sent_e8s: u64,
+    received_e8s: u64,
+}
+
+impl Flow {
+    fn add(&mut self, outgoing: bool, amount_e8s: u64) {
+        if outgoing {
+            self.sent_count += 1;
+            self.sent_e8s = self.sent_e8s.saturating_add(amount_e8s);
+        } else {
+            self.received_count += 1;
+            self.received_e8s = self.received_e8s.saturating_add(amount_e8s);
+        }
+    }
+
+    fn merge(&mut self, other: &Flow) {
+        self.sent_count += other.sent_count;
+        self.received_count += other.received_count;
+        self.sent_e8s = self.sent_e8s.saturating_add(other.sent_e8s);
+        self.received_e8s = self.received_e8s.saturating_add(other.received_e8s);
+    }
+}
+
+impl From<&SpamSummary> for Flow {
+    fn from(summary: &SpamSummary) -> Self {
+        Self {
+            sent_count: summary.sent_count,
+            received_count: summary.received_count,
+            sent_e8s: summary.sent_e8s,
+            received_e8s: summary.received_e8s,
+        }
+    }
+}
+
+///
+/// Graph
+///
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct Graph {
+    pub nodes: Vec<GraphNode>,
+    pub links: Vec<GraphEdge>,
+}
+
+impl Graph {
+    // build
+    // Transfers between two entities become a direct edge. Unlabelled counterparties
+    // touching more than one entity become connector nodes, unless every entity on
+    // them is a Cex or every one is Foundation; connectors with the same label merge.
+    pub fn build(data: &[AccountTransactionsJson]) -> Self {
+        Self::build_excluding(data, &HashSet::new())
+    }
+
+    // build_excluding
+    // as build, leaving out entities whose main account is excluded and every
+    // transfer touching an excluded account
+    pub fn build_excluding(data: &[AccountTransactionsJson], excluded: &HashSet<String>) -> Self {
+        let entities: Vec<&AccountTransactionsJson> =
+            data.iter().filter(|acc| acc.account.as_ref().is_some_and(|account| !excluded.contains(account))).collect();
+
+        let mut nodes = Vec::new();
+        let mut owner: HashMap<&str, usize> = HashMap::new();
+        for (i, acc) in entities.iter().enumerate() {
+            for account in acc.account.iter().chain(&acc.extra_accounts) {
+                owner.entry(account.as_str()).or_insert(i);
+            }
+            nodes.push(GraphNode {
+                id: acc.account.clone().unwrap_or_default(),
+                label: acc.name.clone(),
+                group: format!("{:?}", acc.ty),
+                ty: Some(acc.ty),
+                accounts: acc.account.iter().chain(&acc.extra_accounts).cloned().collect(),
+                main_accounts: Vec::new(),
+            });
+        }
+
+        // direct flows keyed by (source, target), the unlabelled accounts each entity touched
+        // and the neuron stakes of each entity
+        let mut direct: BTreeMap<(usize, usize), Flow> = BTreeMap::new();
+        let mut unknown: BTreeMap<&str, BTreeMap<usize, Flow>> = BTreeMap::new();
+        let mut stakes: BTreeMap<usize, (BTreeSet<&str>, Flow)> = BTreeMap::new();
+        let mut seen_ids = HashSet::new();
+
+        for (i, acc) in entities.iter().enumerate() {
+            let transfers = acc.transactions.iter().filter(|tx| tx.op_type == "Transfer");
+            for tx in transfers.filter(|tx| !excluded.contains(&tx.from) && !excluded.contains(&tx.to)) {
+                let from = owner.get(tx.from.as_str()).copied();
+                let to = owner.get(tx.to.as_str()).copied();
+
+                match (from, to) {
+                    (Some(from), Some(to)) => {
+                        // the same transfer shows up in both entities' lists
+                        if from == to || !seen_ids.insert(tx.id) {
+                            continue;
+                        }
+                        if let Some(flow) = direct.get_mut(&(to, from)) {
+                            flow.add(false, tx.amount_e8s);
+                        } else {
+                            direct.entry((from, to)).or_default().add(true, tx.amount_e8s);
+                        }
+                    }
+                    (Some(from), None) if from == i => {
+                        if is_neuron_stake(&tx.to_label) {
+                            let (accounts, flow) = stakes.entry(i).or_default();
+                            accounts.insert(tx.to.as_str());
+                            flow.add(true, tx.amount_e8s);
+                        } else {
+                            unknown.entry(tx.to.as_str()).or_default().entry(i).or_default().add(true, tx.amount_e8s);
+                        }
+                    }
+                    (None, Some(to)) if to == i => {
+                        if is_neuron_stake(&tx.from_label) {
+                            let (accounts, flow) = stakes.entry(i).or_default();
+                            accounts.insert(tx.from.as_str());
+                            flow.add(false, tx.amount_e8s);
+                        } else {
+                            unknown
+                                .entry(tx.from.as_str())
+                                .or_default()
+                                .entry(i)
+                                .or_default()
+                                .add(false, tx.amount_e8s);
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        let mut links: Vec<GraphEdge> = direct
+            .iter()
+            .map(|((source, target), flow)| GraphEdge::from_flow(&nodes[*source].id, &nodes[*target].id, flow))
+            .collect();
+
+        // connector nodes, merged by label
+        let mut connectors: BTreeMap<String, (BTreeSet<&str>, BTreeMap<usize, Flow>)> = BTreeMap::new();
+        for (account, flows) in unknown {
+            if flows.len() < 2 {
+                continue;
+            }
+            let all = |ty: Type| flows.keys().all(|i| entities[*i].ty == ty);
+            if all(Type::Cex) || all(Type::Foundation) {
+                continue;
+            }
+
+            let label: String = flows.keys().map(|i| initials(&entities[*i].name)).collect();
+            let (accounts, merged) = connectors.entry(label).or_default();
+            accounts.insert(account);
+            for (i, flow) in &flows {
+                merged.entry(*i).or_default().merge(flow);
+            }
+        }
+
+        for (label, (accounts, flows)) in connectors {
+            links.extend(flows.iter().map(|(i, flow)| GraphEdge::from_flow(&nodes[*i].id, &label, flow)));
+            let main_accounts = flows.keys().map(|i| nodes[*i].id.clone()).collect();
+            nodes.push(GraphNode {
+                id: label.clone(),
+                label,
+                group: CONNECTOR_GROUP.to_string(),
+                ty: None,
+                accounts: accounts.into_iter().map(String::from).collect(),
+                main_accounts,
+            });
+        }
+
+        // each entity's neuron stakes share one node, linked to that entity only
+        for (i, (accounts, flow)) in stakes {
+            let id = format!("{}:{}", NEURON_STAKE_GROUP, nodes[i].id);
+            links.push(GraphEdge::from_flow(&nodes[i].id, &id, &flow));
+            nodes.push(GraphNode {
+                id,
+                label: NEURON_STAKE_LABEL.to_string(),
+                group: NEURON_STAKE_GROUP.to_string(),
+                ty: Some(Type::Neuron),
+                accounts: accounts.into_iter().map(String::from).collect(),
+                main_accounts: vec![nodes[i].id.clone()],
+            });
+        }
+
+        // transfers collapsed by the export filter share one spam node
+        let spam: Vec<(usize, Flow)> = entities
+            .iter()
+            .enumerate()
+            .filter_map(|(i, acc)| acc.spam.as_ref().map(|summary| (i, Flow::from(summary))))
+            .collect();
+        if !spam.is_empty() {
+            links.extend(spam.iter().map(|(i, flow)| GraphEdge::from_flow(&nodes[*i].id, SPAM_GROUP, flow)));
+            nodes.push(GraphNode {
+                id: SPAM_GROUP.to_string(),
+                label: "Spam".to_string(),
+                group: SPAM_GROUP.to_string(),
+                ty: Some(Type::Spammer),
+                accounts: Vec::new(),
+                main_accounts: spam.iter().map(|(i, _)| nodes[*i].id.clone()).collect(),
+            });
+        }
+
+        Self { nodes, links }
+    }
+}
+
+// is_neuron_stake
+fn is_neuron_stake(label: &Option<Label>) -> bool {
+    label.as_ref().is_some_and(|label| label.ty == Type::Neuron)
+}
+
+// initials
+// first two characters of the name with whitespace removed
+fn initials(name: &str) -> String {
+    name.chars().filter(|c| !c.is_whitespace()).take(2).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{transactions::SimplifiedTransfer, AccountData};
+
+    fn entity(name: &str, account: &str, ty: Type, transactions: Vec<SimplifiedTransfer>) -> AccountTransactionsJson {
+        let mut acc = AccountTransactionsJson::label_only(&AccountData::new(name, account, ty).unwrap(), None);
+        acc.transactions = transactions;
+        acc
+    }
+
+    fn connectors(graph: &Graph) -> Vec<&GraphNode> {
+        graph.nodes.iter().filter(|node| node.group == CONNECTOR_GROUP).collect()
+    }
+
+    #[test]
+    fn direct_transfers_listed_by_both_entities_count_once() {
+        let both =
+            || vec![SimplifiedTransfer::transfer(1, "aa", "bb", 100), SimplifiedTransfer::transfer(2, "bb", "aa", 30)];
+        let graph = Graph::build(&[
+            entity("Alpha", "aa", Type::Identified, both()),
+            entity("Bravo", "bb", Type::Identified, both()),
+        ]);
+
+        assert_eq!(graph.nodes.len(), 2);
+        assert_eq!(graph.links.len(), 1);
+        let edge = &graph.links[0];
+        assert_eq!((edge.source.as_str(), edge.target.as_str()), ("aa", "bb"));
+        assert_eq!(edge.direction, Direction::Both);
+        assert_eq!(edge.count, 2);
+        assert_eq!((edge.sent_e8s, edge.received_e8s, edge.volume_e8s), (100, 30, 130));
+    }
+
+    #[test]
+    fn shared_unlabelled_account_becomes_connector() {
+        let graph = Graph::build(&[
+            entity("Alpha", "aa", Type::Identified, vec![SimplifiedTransfer::transfer(1, "aa", "xx", 100)]),
+            entity("Bravo", "bb", Type::NodeProvider, vec![SimplifiedTransfer::transfer(2, "xx", "bb", 40)]),
+            // only one entity touches yy
+            entity("Charlie", "cc", Type::Identified, vec![SimplifiedTransfer::transfer(3, "cc", "yy", 5)]),
+        ]);
+
+        let connectors = connectors(&graph);
+        assert_eq!(connectors.len(), 1);
+        assert_eq!(connectors[0].id, "AlBr");
+        assert_eq!(connectors[0].accounts, ["xx"]);
+        assert_eq!(connectors[0].main_accounts, ["aa", "bb"]);
+
+        let edge = |source: &str| graph.links.iter().find(|edge| edge.source == source && edge.target == "AlBr");
+        assert_eq!(edge("aa").map(|edge| (edge.direction, edge.sent_e8s)), Some((Direction::Send, 100)));
+        assert_eq!(edge("bb").map(|edge| (edge.direction, edge.received_e8s)), Some((Direction::Receive, 40)));
+        assert_eq!(graph.links.len(), 2);
+    }
+
+    #[test]
+    fn connectors_between_only_cex_or_only_foundation_are_skipped() {
+        for ty in [Type::Cex, Type::Foundation] {
+            let graph = Graph::build(&[
+                entity("Alpha", "aa", ty, vec![SimplifiedTransfer::transfer(1, "aa", "xx", 100)]),
+                entity("Bravo", "bb", ty, vec![SimplifiedTransfer::transfer(2, "bb", "xx", 100)]),
+            ]);
+            assert!(connectors(&graph).is_empty(), "{ty:?}");
+            assert!(graph.links.is_empty(), "{ty:?}");
+        }
+
+        let graph = Graph::build(&[
+            entity("Alpha", "aa", Type::Cex, vec![SimplifiedTransfer::transfer(1, "aa", "xx", 100)]),
+            entity("Bravo", "bb", Type::Foundation, vec![SimplifiedTransfer::transfer(2, "bb", "xx", 100)]),
+        ]);
+        assert_eq!(connectors(&graph).len(), 1);
+    }
+
+    #[test]
+    fn connectors_with_the_same_label_merge() {
+        let graph = Graph::build(&[
+            entity(
+                "Alpha",
+                "aa",
+                Type::Identified,
+                vec![SimplifiedTransfer::transfer(1, "aa", "xx", 100), SimplifiedTransfer::transfer(2, "aa", "yy", 50)],
+            ),
+            entity(
+                "Bravo",
+                "bb",
+                Type::Identified,
+                vec![SimplifiedTransfer::transfer(3, "xx", "bb", 20), SimplifiedTransfer::transfer(4, "yy", "bb", 10)],
+            ),
+        ]);
+
+        let connectors = connectors(&graph);
+        assert_eq!(connectors.len(), 1);
+        assert_eq!(connectors[0].accounts, ["xx", "yy"]);
+
+        let alpha = graph.links.iter().find(|edge| edge.source == "aa").unwrap();
+        assert_eq!((alpha.count, alpha.sent_e8s), (2, 150));
+        let bravo = graph.links.iter().find(|edge| edge.source == "bb").unwrap();
+        assert_eq!((bravo.count, bravo.received_e8s), (2, 30));
+    }
+
+    #[test]
+    fn neuron_stakes_get_one_node_per_entity() {
+        let stake = |id, from: &str, to: &str, amount_e8s| SimplifiedTransfer {
+            to_label: Some(Label { name: NEURON_STAKE_LABEL.to_string(), ty: Type::Neuron }),
+            ..SimplifiedTransfer::transfer(id, from, to, amount_e8s)
+        };
+        let graph = Graph::build(&[
+            entity("Alpha", "aa", Type::Identified, vec![stake(1, "aa", "n1", 100), stake(2, "aa", "n2", 50)]),
+            // a top up of Alpha's neuron stays off Alpha's node and makes no connector
+            entity("Bravo", "bb", Type::Identified, vec![stake(3, "bb", "n1", 20)]),
+        ]);
+
+        let stakes: Vec<&GraphNode> = graph.nodes.iter().filter(|node| node.group == NEURON_STAKE_GROUP).collect();
+        assert_eq!(stakes.len(), 2);
+        assert!(connectors(&graph).is_empty());
+        assert_eq!(stakes[0].id, "neuron-stake:aa");
+        assert_eq!(stakes[0].accounts, ["n1", "n2"]);
+        assert_eq!(stakes[0].main_accounts, ["aa"]);
+        assert_eq!(stakes[1].accounts, ["n1"]);
+
+        let alpha = graph.links.iter().find(|edge| edge.target == "neuron-stake:aa").unwrap();
+        assert_eq!(
+            (alpha.source.as_str(), alpha.direction, alpha.sent_count, alpha.sent_e8s),
+            ("aa", Direction::Send, 2, 150)
+        );
+    }
+}
@@ -0,0 +1,230 @@
+use crate::graph::{Direction, Graph, GraphEdge};
+use std::fmt::Write;
+
+const E8S_PER_ICP: f64 = 100_000_000.0;
+
+// to_graphml
+// edges point the way the money moved, see transfer_edges
+pub fn to_graphml(graph: &Graph) -> String {
+    let mut out = String::new();
+    out.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    out.push_str("<graphml xmlns=\"http://graphml.graphdrawing.org/xmlns\">\n");
+    out.push_str("  <key id=\"name\" for=\"node\" attr.name=\"name\" attr.type=\"string\"/>\n");
+    out.push_str("  <key id=\"type\" for=\"node\" attr.name=\"type\" attr.type=\"string\"/>\n");
+    out.push_str("  <key id=\"count\" for=\"edge\" attr.name=\"count\" attr.type=\"long\"/>\n");
+    out.push_str("  <key id=\"total_e8s\" for=\"edge\" attr.name=\"total_e8s\" attr.type=\"long\"/>\n");
+    out.push_str("  <graph id=\"transfers\" edgedefault=\"directed\">\n");
+
+    for node in &graph.nodes {
+        let _ = writeln!(
+            out,
+            "    <node id=\"{}\"><data key=\"name\">{}</data><data key=\"type\">{}</data></node>",
+            xml_escape(&node.id),
+            xml_escape(&node.label),
+            xml_escape(&node.group)
+        );
+    }
+    for (i, edge) in transfer_edges(graph).enumerate() {
+        let _ = writeln!(
+            out,
+            "    <edge id=\"e{i}\" source=\"{}\" target=\"{}\"><data key=\"count\">{}</data>\
+             <data key=\"total_e8s\">{}</data></edge>",
+            xml_escape(edge.source),
+            xml_escape(edge.target),
+            edge.count,
+            edge.total_e8s
+        );
+    }
+
+    out.push_str("  </graph>\n</graphml>\n");
+    out
+}
+
+// to_gexf
+// edges point the way the money moved, weighted by the volume in ICP
+pub fn to_gexf(graph: &Graph) -> String {
+    let mut out = String::new();
+    out.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    out.push_str("<gexf xmlns=\"http://gexf.net/1.3\" version=\"1.3\">\n");
+    out.push_str("  <graph defaultedgetype=\"directed\" mode=\"static\">\n");
+    out.push_str("    <attributes class=\"node\">\n");
+    out.push_str("      <attribute id=\"type\" title=\"type\" type=\"string\"/>\n");
+    out.push_str("    </attributes>\n");
+    out.push_str("    <attributes class=\"edge\">\n");
+    out.push_str("      <attribute id=\"count\" title=\"count\" type=\"long\"/>\n");
+    out.push_str("      <attribute id=\"total_e8s\" title=\"total_e8s\" type=\"long\"/>\n");
+    out.push_str("    </attributes>\n");
+
+    out.push_str("    <nodes>\n");
+    for node in &graph.nodes {
+        let _ = writeln!(
+            out,
+            "      <node id=\"{}\" label=\"{}\"><attvalues><attvalue for=\"type\" value=\"{}\"/></attvalues></node>",
+            xml_escape(&node.id),
+            xml_escape(&node.label),
+            xml_escape(&node.group)
+        );
+    }
+    out.push_str("    </nodes>\n");
+
+    out.push_str("    <edges>\n");
+    for (i, edge) in transfer_edges(graph).enumerate() {
+        let _ = writeln!(
+            out,
+            "      <edge id=\"{i}\" source=\"{}\" target=\"{}\" weight=\"{}\"><attvalues>\
+             <attvalue for=\"count\" value=\"{}\"/><attvalue for=\"total_e8s\" value=\"{}\"/>\
+             </attvalues></edge>",
+            xml_escape(edge.source),
+            xml_escape(edge.target),
+            edge.total_e8s as f64 / E8S_PER_ICP,
+            edge.count,
+            edge.total_e8s
+        );
+    }
+    out.push_str("    </edges>\n");
+
+    out.push_str("  </graph>\n</gexf>\n");
+    out
+}
+
+// to_dot
+// `dir` makes graphviz draw the arrowheads the way the money moved
+pub fn to_dot(graph: &Graph) -> String {
+    let mut out = String::from("digraph transfers {\n");
+
+    for node in &graph.nodes {
+        let _ = writeln!(
+            out,
+            "  \"{}\" [label=\"{}\", type=\"{}\"];",
+            dot_escape(&node.id),
+            dot_escape(&node.label),
+            dot_escape(&node.group)
+        );
+    }
+    for edge in &graph.links {
+        let dir = match edge.direction {
+            Direction::Send => "forward",
+            Direction::Receive => "back",
+            Direction::Both => "both",
+        };
+        let _ = writeln!(
+            out,
+            "  \"{}\" -> \"{}\" [direction=\"{}\", count={}, total_e8s={}, dir={dir}];",
+            dot_escape(&edge.source),
+            dot_escape(&edge.target),
+            direction_name(edge.direction),
+            edge.count,
+            edge.volume_e8s
+        );
+    }
+
+    out.push_str("}\n");
+    out
+}
+
+///
+/// TransferEdge
+///
+/// One direction of a GraphEdge, from the payer to the payee.
+///
+
+#[derive(Debug)]
+struct TransferEdge<'a> {
+    source: &'a str,
+    target: &'a str,
+    count: u64,
+    total_e8s: u64,
+}
+
+// transfer_edges
+// GraphML and GEXF have no notion of a receive or two-way edge, so a RECEIVE
+// edge is flipped and a BOTH edge is split into one edge per direction
+fn transfer_edges(graph: &Graph) -> impl Iterator<Item = TransferEdge<'_>> {
+    graph.links.iter().flat_map(|edge: &GraphEdge| {
+        let sent = TransferEdge {
+            source: &edge.source,
+            target: &edge.target,
+            count: edge.sent_count,
+            total_e8s: edge.sent_e8s,
+        };
+        let received = TransferEdge {
+            source: &edge.target,
+            target: &edge.source,
+            count: edge.received_count,
+            total_e8s: edge.received_e8s,
+        };
+        [sent, received].into_iter().filter(|transfer| transfer.count > 0)
+    })
+}
+
+fn direction_name(direction: Direction) -> &'static str {
+    match direction {
+        Direction::Send => "SEND",
+        Direction::Receive => "RECEIVE",
+        Direction::Both => "BOTH",
+    }
+}
+
+fn xml_escape(value: &str) -> String {
+    value.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;").replace('\'', "&apos;")
+}
+
+fn dot_escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph::GraphNode;
+
+    fn node(id: &str) -> GraphNode {
+        GraphNode {
+            id: id.to_string(),
+            label: id.to_string(),
+            group: "Identified".to_string(),
+            ty: None,
+            accounts: Vec::new(),
+            main_accounts: Vec::new(),
+        }
+    }
+
+    fn edge(direction: Direction, sent: (u64, u64), received: (u64, u64)) -> GraphEdge {
+        GraphEdge {
+            source: "aa".to_string(),
+            target: "bb".to_string(),
+            direction,
+            count: sent.0 + received.0,
+            volume_e8s: sent.1 + received.1,
+            sent_count: sent.0,
+            received_count: received.0,
+            sent_e8s: sent.1,
+            received_e8s: received.1,
+        }
+    }
+
+    fn edges(graph: &Graph) -> Vec<(&str, &str, u64, u64)> {
+        transfer_edges(graph).map(|edge| (edge.source, edge.target, edge.count, edge.total_e8s)).collect()
+    }
+
+    #[test]
+    fn transfer_edges_follow_the_money() {
+        let graph = |edge| Graph { nodes: vec![node("aa"), node("bb")], links: vec![edge] };
+
+        assert_eq!(edges(&graph(edge(Direction::Send, (2, 100), (0, 0)))), [("aa", "bb", 2, 100)]);
+        assert_eq!(edges(&graph(edge(Direction::Receive, (0, 0), (1, 30)))), [("bb", "aa", 1, 30)]);
+        assert_eq!(
+            edges(&graph(edge(Direction::Both, (2, 100), (1, 30)))),
+            [("aa", "bb", 2, 100), ("bb", "aa", 1, 30)]
+        );
+    }
+
+    #[test]
+    fn graphml_and_gexf_flip_receive_edges() {
+        let graph =
+            Graph { nodes: vec![node("aa"), node("bb")], links: vec![edge(Direction::Receive, (0, 0), (1, 30))] };
+
+        assert!(to_graphml(&graph).contains("source=\"bb\" target=\"aa\""));
+        assert!(to_gexf(&graph).contains("source=\"bb\" target=\"aa\""));
+    }
+}
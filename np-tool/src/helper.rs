@@ -46,3 +46,272 @@ pub fn is_valid_account_id(account_id_hex: &str) -> Result<bool, Box<dyn std::er
 
     Ok(computed_checksum == provided_checksum)
 }
+
+// parse_subaccount
+// hex subaccount, left padded with zeros to 32 bytes
+pub fn parse_subaccount(hex_str: &str) -> Result<[u8; 32], String> {
+    if hex_str.is_empty() || hex_str.len() > 64 {
+        return Err(format!("invalid subaccount length: '{hex_str}'"));
+    }
+
+    let padded = format!("{hex_str:0>64}");
+    let bytes = hex::decode(&padded).map_err(|e| format!("invalid subaccount '{hex_str}': {e}"))?;
+    let mut subaccount = [0u8; 32];
+    subaccount.copy_from_slice(&bytes);
+
+    Ok(subaccount)
+}
+
+// icrc1_account_text
+// ICRC-1 textual account: the principal alone for the default subaccount, otherwise
+// `<principal>-<checksum>.<subaccount in hex without leading zeros>`
+pub fn icrc1_account_text(owner: &Principal, subaccount: Option<&[u8]>) -> String {
+    let Some(subaccount) = subaccount.filter(|sub| sub.iter().any(|b| *b != 0)) else {
+        return owner.to_text();
+    };
+    let mut padded = vec![0u8; 32usize.saturating_sub(subaccount.len())];
+    padded.extend_from_slice(subaccount);
+
+    let mut crc_hasher = Crc32Hasher::new();
+    crc_hasher.update(owner.as_slice());
+    crc_hasher.update(&padded);
+    let checksum = base32(&crc_hasher.finalize().to_be_bytes());
+
+    format!("{owner}-{checksum}.{}", hex::encode(&padded).trim_start_matches('0'))
+}
+
+// parse_icrc1_account
+// the inverse of icrc1_account_text, rejecting bad checksums and non-canonical forms
+pub fn parse_icrc1_account(text: &str) -> Result<(Principal, Option<[u8; 32]>), String> {
+    let Some((owner_checksum, sub)) = text.rsplit_once('.') else {
+        let owner = Principal::from_text(text).map_err(|e| format!("invalid principal '{text}': {e}"))?;
+        return Ok((owner, None));
+    };
+    let (owner, _) = owner_checksum.rsplit_once('-').ok_or_else(|| format!("missing checksum in '{text}'"))?;
+    let owner = Principal::from_text(owner).map_err(|e| format!("invalid principal in '{text}': {e}"))?;
+    if sub.starts_with('0') {
+        return Err(format!("subaccount has leading zeros in '{text}'"));
+    }
+    let subaccount = parse_subaccount(sub)?;
+    if icrc1_account_text(&owner, Some(&subaccount)) != text {
+        return Err(format!("invalid checksum in '{text}'"));
+    }
+
+    Ok((owner, Some(subaccount)))
+}
+
+// base32
+// lowercase RFC 4648 without padding, as used in principal and account text
+fn base32(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8] = b"abcdefghijklmnopqrstuvwxyz234567";
+    let (mut buffer, mut bits, mut out) = (0u32, 0u32, String::new());
+    for byte in bytes {
+        buffer = (buffer << 8) | u32::from(*byte);
+        bits += 8;
+        while bits >= 5 {
+            bits -= 5;
+            out.push(ALPHABET[((buffer >> bits) & 31) as usize] as char);
+        }
+        buffer &= (1 << bits) - 1;
+    }
+    if bits > 0 {
+        out.push(ALPHABET[((buffer << (5 - bits)) & 31) as usize] as char);
+    }
+
+    out
+}
+
+// block_range
+// [start, start + count) capped at the log length, or the latest count blocks without a start
+pub fn block_range(start_block: Option<u64>, block_count: u64, log_length: u64) -> (u64, u64) {
+    match start_block {
+        Some(start) => (start, start.saturating_add(block_count).min(log_length)),
+        None => (log_length.saturating_sub(block_count), log_length),
+    }
+}
+
+// parse_timestamp
+// accepts unix seconds or a YYYY-MM-DD date (UTC), with dates resolving to the
+// start of the day, or to its last second when end_of_day is set
+pub fn parse_timestamp(input: &str, end_of_day: bool) -> Result<u64, String> {
+    let input = input.trim();
+    if !input.is_empty() && input.chars().all(|c| c.is_ascii_digit()) {
+        return input.parse::<u64>().map_err(|e| format!("invalid unix timestamp '{input}': {e}"));
+    }
+
+    let parts: Vec<&str> = input.split('-').collect();
+    let [year, month, day] = parts.as_slice() else {
+        return Err(format!("expected YYYY-MM-DD or unix seconds, got '{input}'"));
+    };
+    let year: i64 = year.parse().map_err(|_| format!("invalid year in '{input}'"))?;
+    let month: u32 = month.parse().map_err(|_| format!("invalid month in '{input}'"))?;
+    let day: u32 = day.parse().map_err(|_| format!("invalid day in '{input}'"))?;
+    if year < 1970 || !(1..=12).contains(&month) || day == 0 || day > days_in_month(year, month) {
+        return Err(format!("date out of range: '{input}'"));
+    }
+
+    let start = days_from_civil(year, month, day) as u64 * 86_400;
+    Ok(if end_of_day { start + 86_399 } else { start })
+}
+
+// format_timestamp
+// unix seconds as an ISO 8601 UTC string
+pub fn format_timestamp(timestamp_seconds: u64) -> String {
+    let days = (timestamp_seconds / 86_400) as i64;
+    let secs = timestamp_seconds % 86_400;
+    let (year, month, day) = civil_from_days(days);
+
+    format!("{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}Z", secs / 3600, secs % 3600 / 60, secs % 60)
+}
+
+// days_in_month
+fn days_in_month(year: i64, month: u32) -> u32 {
+    match month {
+        2 if (year % 4 == 0 && year % 100 != 0) || year % 400 == 0 => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+// days_from_civil
+// days since 1970-01-01 for a proleptic gregorian date
+fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let yoe = year - era * 400;
+    let month = month as i64;
+    let doy = (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + day as i64 - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+
+    era * 146_097 + doe - 719_468
+}
+
+// civil_from_days
+// inverse of days_from_civil
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + i64::from(month <= 2);
+
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_timestamp_epoch() {
+        assert_eq!(parse_timestamp("1970-01-01", false), Ok(0));
+        assert_eq!(parse_timestamp("1970-01-01", true), Ok(86_399));
+        assert_eq!(parse_timestamp("0", false), Ok(0));
+    }
+
+    #[test]
+    fn parse_timestamp_unix_seconds_ignore_end_of_day() {
+        assert_eq!(parse_timestamp("1709164800", true), Ok(1_709_164_800));
+    }
+
+    #[test]
+    fn parse_timestamp_leap_days() {
+        assert_eq!(parse_timestamp("2024-02-29", false), Ok(1_709_164_800));
+        assert_eq!(parse_timestamp("2000-02-29", false), Ok(951_782_400));
+        assert_eq!(parse_timestamp("2024-03-01", false), Ok(1_709_251_200));
+        assert!(parse_timestamp("2023-02-29", false).is_err());
+        assert!(parse_timestamp("1900-02-29", false).is_err());
+    }
+
+    #[test]
+    fn parse_timestamp_end_of_day() {
+        assert_eq!(parse_timestamp("2024-12-31", true), Ok(1_735_689_599));
+        assert_eq!(parse_timestamp("2024-12-31", true).map(|ts| ts + 1), parse_timestamp("2025-01-01", false));
+    }
+
+    #[test]
+    fn parse_timestamp_invalid_dates() {
+        for input in
+            ["2024-02-30", "2024-04-31", "2024-13-01", "2024-00-10", "2024-01-00", "1969-12-31", "2024-1", "", "x"]
+        {
+            assert!(parse_timestamp(input, false).is_err(), "{input} should be rejected");
+        }
+    }
+
+    #[test]
+    fn civil_round_trip() {
+        for days in [-1, 0, 59, 10_956, 11_016, 19_782, 20_000, 100_000] {
+            let (year, month, day) = civil_from_days(days);
+            assert_eq!(days_from_civil(year, month, day), days);
+        }
+        assert_eq!(format_timestamp(1_709_164_800), "2024-02-29T00:00:00Z");
+        assert_eq!(format_timestamp(86_399), "1970-01-01T23:59:59Z");
+    }
+
+    #[test]
+    fn parse_subaccount_pads_left() {
+        let mut expected = [0u8; 32];
+        expected[31] = 0x01;
+        assert_eq!(parse_subaccount("1"), Ok(expected));
+        expected[30] = 0x0a;
+        assert_eq!(parse_subaccount("0a01"), Ok(expected));
+        assert_eq!(parse_subaccount(&"ff".repeat(32)), Ok([0xff; 32]));
+    }
+
+    #[test]
+    fn parse_subaccount_rejects_invalid() {
+        assert!(parse_subaccount("").is_err());
+        assert!(parse_subaccount(&"0".repeat(65)).is_err());
+        assert!(parse_subaccount("zz").is_err());
+    }
+
+    #[test]
+    fn icrc1_account_text_round_trip() {
+        let owner = Principal::from_text("k2t6j-2nvnp-4zjm3-25dtz-6xhaa-c7boj-5gayf-oj3xs-i43lp-teztq-6ae").unwrap();
+        let subaccount: Vec<u8> = (1..=32).collect();
+        let text = icrc1_account_text(&owner, Some(&subaccount));
+
+        assert_eq!(
+            text,
+            "k2t6j-2nvnp-4zjm3-25dtz-6xhaa-c7boj-5gayf-oj3xs-i43lp-teztq-6ae-dfxgiyy.\
+             102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f20"
+        );
+        assert_eq!(parse_icrc1_account(&text), Ok((owner, Some(subaccount.try_into().unwrap()))));
+    }
+
+    #[test]
+    fn icrc1_account_text_default_subaccount() {
+        let owner = Principal::from_text("k2t6j-2nvnp-4zjm3-25dtz-6xhaa-c7boj-5gayf-oj3xs-i43lp-teztq-6ae").unwrap();
+
+        assert_eq!(icrc1_account_text(&owner, None), owner.to_text());
+        assert_eq!(icrc1_account_text(&owner, Some(&[0; 32])), owner.to_text());
+        assert_eq!(parse_icrc1_account(&owner.to_text()), Ok((owner, None)));
+    }
+
+    #[test]
+    fn parse_icrc1_account_rejects_malformed() {
+        let owner = "k2t6j-2nvnp-4zjm3-25dtz-6xhaa-c7boj-5gayf-oj3xs-i43lp-teztq-6ae";
+        for text in [
+            format!("{owner}-aaaaaaa.1"),
+            format!("{owner}.1"),
+            format!("{owner}-dfxgiyy.0102"),
+            format!("{owner}-dfxgiyy.zz"),
+            "not-a-principal".to_string(),
+        ] {
+            assert!(parse_icrc1_account(&text).is_err(), "{text} should be rejected");
+        }
+    }
+
+    #[test]
+    fn block_range_with_and_without_start() {
+        assert_eq!(block_range(Some(10), 5, 100), (10, 15));
+        assert_eq!(block_range(Some(98), 5, 100), (98, 100));
+        assert_eq!(block_range(None, 30, 100), (70, 100));
+        assert_eq!(block_range(None, 300, 100), (0, 100));
+    }
+}
@@ -0,0 +1,305 @@
+use crate::{transactions::Valuation, AccountData, Type};
+use serde::{Deserialize, Deserializer};
+use std::collections::HashSet;
+
+// ticker snapshot taken from the ICPSwap coingecko-style tickers endpoint
+const ICPSWAP_JSON: &str = include_str!("icpswap.json");
+
+pub const ICP_LEDGER_ID: &str = "ryjl3-tyaaa-aaaaa-aaaba-cai";
+
+///
+/// IcpSwapTicker
+///
+/// One pool; `last_price` is the number of base tokens paid for one target token.
+/// Numbers arrive as strings, and unknown prices as "nan".
+///
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct IcpSwapTicker {
+    pub ticker_id: String,
+    pub ticker_name: String,
+    pub base_id: String,
+    pub base_currency: String,
+    pub target_id: String,
+    pub target_currency: String,
+    #[serde(deserialize_with = "decimal")]
+    pub last_price: Option<f64>,
+    #[serde(deserialize_with = "decimal")]
+    pub base_volume: Option<f64>,
+    #[serde(deserialize_with = "decimal")]
+    pub target_volume: Option<f64>,
+    #[serde(rename = "base_volume_24H", deserialize_with = "decimal")]
+    pub base_volume_24h: Option<f64>,
+    #[serde(rename = "target_volume_24H", deserialize_with = "decimal")]
+    pub target_volume_24h: Option<f64>,
+    #[serde(deserialize_with = "decimal")]
+    pub total_volume_usd: Option<f64>,
+    #[serde(rename = "volume_usd_24H", deserialize_with = "decimal")]
+    pub volume_usd_24h: Option<f64>,
+    #[serde(deserialize_with = "decimal")]
+    pub fee_usd: Option<f64>,
+    #[serde(deserialize_with = "decimal")]
+    pub liquidity_in_usd: Option<f64>,
+}
+
+impl IcpSwapTicker {
+    // price_of
+    // price of one `ledger_id` token expressed in the other side of the pool
+    pub fn price_of(&self, ledger_id: &str) -> Option<f64> {
+        let price = self.last_price.filter(|p| *p > 0.0)?;
+        if self.base_id == ledger_id {
+            Some(1.0 / price)
+        } else if self.target_id == ledger_id {
+            Some(price)
+        } else {
+            None
+        }
+    }
+
+    // other_side
+    pub fn other_side(&self, ledger_id: &str) -> Option<&str> {
+        if self.base_id == ledger_id {
+            Some(&self.target_id)
+        } else if self.target_id == ledger_id {
+            Some(&self.base_id)
+        } else {
+            None
+        }
+    }
+
+    fn liquidity(&self) -> f64 {
+        self.liquidity_in_usd.unwrap_or(0.0)
+    }
+}
+
+///
+/// IcpSwapSnapshot
+///
+
+#[derive(Debug, Clone)]
+pub struct IcpSwapSnapshot {
+    pub tickers: Vec<IcpSwapTicker>,
+}
+
+impl IcpSwapSnapshot {
+    // load
+    pub fn load() -> Result<Self, serde_json::Error> {
+        Self::from_json(ICPSWAP_JSON)
+    }
+
+    // from_json
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        let tickers = serde_json::from_str(json)?;
+
+        Ok(Self { tickers })
+    }
+
+    // entries
+    // every pool canister and token ledger as a DeFi address book entry
+    pub fn entries(&self) -> Vec<AccountData> {
+        let mut seen = HashSet::new();
+        let mut entries = Vec::new();
+
+        for ticker in &self.tickers {
+            if seen.insert(ticker.ticker_id.as_str()) {
+                match AccountData::new(&ticker.ticker_name, &ticker.ticker_id, Type::Defi) {
+                    Ok(entry) => entries.push(entry),
+                    Err(e) => eprintln!("Skipping ICPSwap pool {e}"),
+                }
+            }
+        }
+
+        for ticker in &self.tickers {
+            for (ledger_id, currency) in
+                [(&ticker.base_id, &ticker.base_currency), (&ticker.target_id, &ticker.target_currency)]
+            {
+                if seen.insert(ledger_id.as_str()) {
+                    match AccountData::new(&format!("{currency} Ledger"), ledger_id, Type::Defi) {
+                        Ok(entry) => entries.push(entry),
+                        Err(e) => eprintln!("Skipping ICPSwap ledger {e}"),
+                    }
+                }
+            }
+        }
+
+        entries
+    }
+
+    // deepest_pool
+    // the pool pairing the two ledgers with the most liquidity
+    fn deepest_pool(&self, ledger_id: &str, quote_id: &str) -> Option<&IcpSwapTicker> {
+        self.tickers
+            .iter()
+            .filter(|t| t.other_side(ledger_id) == Some(quote_id) && t.price_of(ledger_id).is_some())
+            .max_by(|a, b| a.liquidity().total_cmp(&b.liquidity()))
+    }
+
+    // price_in_icp
+    // priced against the deepest direct ICP pool
+    pub fn price_in_icp(&self, ledger_id: &str) -> Option<f64> {
+        if ledger_id == ICP_LEDGER_ID {
+            return Some(1.0);
+        }
+
+        self.deepest_pool(ledger_id, ICP_LEDGER_ID)?.price_of(ledger_id)
+    }
+
+    // icp_price_usd
+    // implied by the deepest ICP pool against a USD stablecoin
+    pub fn icp_price_usd(&self) -> Option<f64> {
+        self.tickers
+            .iter()
+            .filter(|t| {
+                t.other_side(ICP_LEDGER_ID).is_some_and(|_| {
+                    ["ckUSDC", "ckUSDT"].iter().any(|c| *c == t.base_currency || *c == t.target_currency)
+                })
+            })
+            .filter(|t| t.price_of(ICP_LEDGER_ID).is_some())
+            .max_by(|a, b| a.liquidity().total_cmp(&b.liquidity()))
+            .and_then(|t| t.price_of(ICP_LEDGER_ID))
+    }
+
+    // value_in_icp
+    // `amount` is in whole tokens, not base units
+    pub fn value_in_icp(&self, ledger_id: &str, amount: f64) -> Option<f64> {
+        self.price_in_icp(ledger_id).map(|price| price * amount)
+    }
+
+    // value_in_usd
+    pub fn value_in_usd(&self, ledger_id: &str, amount: f64) -> Option<f64> {
+        Some(self.value_in_icp(ledger_id, amount)? * self.icp_price_usd()?)
+    }
+
+    // liquidity_in_usd
+    // total liquidity across all pools the ledger trades in
+    pub fn liquidity_in_usd(&self, ledger_id: &str) -> f64 {
+        self.tickers.iter().filter(|t| t.other_side(ledger_id).is_some()).map(IcpSwapTicker::liquidity).sum()
+    }
+
+    // valuation
+    // pools are valued by their liquidity, ledgers by their token price
+    pub fn valuation(&self, canister_id: &str) -> Option<Valuation> {
+        if let Some(pool) = self.tickers.iter().find(|t| t.ticker_id == canister_id) {
+            return Some(Valuation { price_icp: None, price_usd: None, liquidity_usd: pool.liquidity() });
+        }
+
+        if !self.tickers.iter().any(|t| t.other_side(canister_id).is_some()) {
+            return None;
+        }
+
+        Some(Valuation {
+            price_icp: self.price_in_icp(canister_id),
+            price_usd: self.value_in_usd(canister_id, 1.0),
+            liquidity_usd: self.liquidity_in_usd(canister_id),
+        })
+    }
+}
+
+// decimal
+fn decimal<'de, D>(deserializer: D) -> Result<Option<f64>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let raw = String::deserialize(deserializer)?;
+    let value: f64 = raw.trim().parse().map_err(serde::de::Error::custom)?;
+
+    Ok(value.is_finite().then_some(value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FIZZ: &str = "3iygf-oaaaa-aaaap-abzya-cai";
+    const CKUSDC: &str = "xevnm-gaaaa-aaaar-qafnq-cai";
+    const POOLS: [&str; 4] = [
+        "m2wg2-eiaaa-aaaag-qdfsa-cai",
+        "tnyoa-2iaaa-aaaag-qkgfa-cai",
+        "seq7r-2yaaa-aaaag-qdh5a-cai",
+        "o2zmb-eqaaa-aaaag-qjyga-cai",
+    ];
+
+    fn ticker(
+        pool: &str,
+        (base, base_id): (&str, &str),
+        (target, target_id): (&str, &str),
+        price: &str,
+        liquidity: &str,
+    ) -> String {
+        format!(
+            r#"{{"ticker_id": "{pool}", "ticker_name": "{base}_{target}", "base_id": "{base_id}", "base_currency": "{base}",
+                "target_id": "{target_id}", "target_currency": "{target}", "last_price": "{price}", "base_volume": "0",
+                "target_volume": "0", "base_volume_24H": "0", "target_volume_24H": "0", "total_volume_usd": "0",
+                "volume_usd_24H": "0", "fee_usd": "0", "liquidity_in_usd": "{liquidity}"}}"#
+        )
+    }
+
+    fn snapshot(tickers: &[String]) -> IcpSwapSnapshot {
+        IcpSwapSnapshot::from_json(&format!("[{}]", tickers.join(","))).unwrap()
+    }
+
+    fn fizz_icp(pool: &str, price: &str, liquidity: &str) -> String {
+        ticker(pool, ("fizz", FIZZ), ("ICP", ICP_LEDGER_ID), price, liquidity)
+    }
+
+    #[test]
+    fn price_of_inverts_for_the_base_token() {
+        // 0.5 fizz buys one ICP
+        let snapshot = snapshot(&[fizz_icp(POOLS[0], "0.5", "10"), fizz_icp(POOLS[1], "0", "10")]);
+        let (pool, unpriced) = (&snapshot.tickers[0], &snapshot.tickers[1]);
+
+        assert_eq!(pool.price_of(ICP_LEDGER_ID), Some(0.5));
+        assert_eq!(pool.price_of(FIZZ), Some(2.0));
+        assert_eq!(pool.price_of(CKUSDC), None);
+        assert_eq!(unpriced.price_of(FIZZ), None);
+    }
+
+    #[test]
+    fn deepest_pool_chooses_by_liquidity() {
+        let snapshot = snapshot(&[
+            fizz_icp(POOLS[0], "0.5", "10"),
+            fizz_icp(POOLS[1], "0.25", "1000"),
+            // deeper still, but without a price
+            fizz_icp(POOLS[2], "nan", "5000"),
+        ]);
+
+        assert_eq!(snapshot.deepest_pool(FIZZ, ICP_LEDGER_ID).unwrap().ticker_id, POOLS[1]);
+        assert_eq!(snapshot.price_in_icp(FIZZ), Some(4.0));
+        assert_eq!(snapshot.price_in_icp(ICP_LEDGER_ID), Some(1.0));
+        assert_eq!(snapshot.price_in_icp(CKUSDC), None);
+    }
+
+    #[test]
+    fn icp_price_usd_comes_from_a_stablecoin_pool() {
+        let snapshot = snapshot(&[
+            // 0.2 ICP buys one ckUSDC
+            ticker(POOLS[3], ("ICP", ICP_LEDGER_ID), ("ckUSDC", CKUSDC), "0.2", "100"),
+            fizz_icp(POOLS[0], "0.5", "1000"),
+        ]);
+
+        assert_eq!(snapshot.icp_price_usd(), Some(5.0));
+        assert_eq!(snapshot.value_in_usd(FIZZ, 3.0), Some(30.0));
+        assert_eq!(IcpSwapSnapshot { tickers: snapshot.tickers[1..].to_vec() }.icp_price_usd(), None);
+    }
+
+    #[test]
+    fn decimal_reads_nan_as_unknown() {
+        let snapshot = snapshot(&[fizz_icp(POOLS[0], "nan", " 12.5 ")]);
+
+        assert_eq!(snapshot.tickers[0].last_price, None);
+        assert_eq!(snapshot.tickers[0].liquidity_in_usd, Some(12.5));
+        assert!(IcpSwapSnapshot::from_json(&format!("[{}]", fizz_icp(POOLS[0], "cheap", "1"))).is_err());
+    }
+
+    #[test]
+    fn entries_list_each_pool_and_ledger_once() {
+        let snapshot = snapshot(&[
+            fizz_icp(POOLS[0], "0.5", "10"),
+            fizz_icp(POOLS[0], "0.5", "10"),
+            ticker(POOLS[3], ("ICP", ICP_LEDGER_ID), ("ckUSDC", CKUSDC), "0.2", "100"),
+        ]);
+        let names: Vec<String> = snapshot.entries().into_iter().map(|entry| entry.name).collect();
+
+        assert_eq!(names, ["fizz_ICP", "ICP_ckUSDC", "fizz Ledger", "ICP Ledger", "ckUSDC Ledger"]);
+    }
+}
@@ -0,0 +1,499 @@
+use crate::{
+    canister::CanisterBackend,
+    icpswap::IcpSwapSnapshot,
+    transactions::{Account, DateRangeFilter},
+    AccountData,
+};
+use candid::{CandidType, Decode, Encode, Nat, Principal};
+use serde::{Deserialize, Serialize};
+
+const ICRC_INDEX_MAX_RESULTS: u64 = 10000;
+
+#[derive(CandidType, Deserialize)]
+pub struct GetIcrcAccountTransactionsArgs {
+    pub account: Account,
+    pub start: Option<Nat>,
+    pub max_results: Nat,
+}
+
+#[derive(CandidType, Deserialize)]
+pub struct IcrcMint {
+    pub to: Account,
+    pub amount: Nat,
+    pub memo: Option<serde_bytes::ByteBuf>,
+}
+
+#[derive(CandidType, Deserialize)]
+pub struct IcrcBurn {
+    pub from: Account,
+    pub amount: Nat,
+    pub memo: Option<serde_bytes::ByteBuf>,
+}
+
+#[derive(CandidType, Deserialize)]
+pub struct IcrcTransfer {
+    pub from: Account,
+    pub to: Account,
+    pub amount: Nat,
+    pub fee: Option<Nat>,
+    pub memo: Option<serde_bytes::ByteBuf>,
+}
+
+#[derive(CandidType, Deserialize)]
+pub struct IcrcApprove {
+    pub from: Account,
+    pub spender: Account,
+    pub amount: Nat,
+    pub fee: Option<Nat>,
+    pub memo: Option<serde_bytes::ByteBuf>,
+}
+
+#[derive(CandidType, Deserialize)]
+pub struct IcrcTransaction {
+    pub kind: String,
+    pub mint: Option<IcrcMint>,
+    pub burn: Option<IcrcBurn>,
+    pub transfer: Option<IcrcTransfer>,
+    pub approve: Option<IcrcApprove>,
+    pub timestamp: u64,
+}
+
+#[derive(CandidType, Deserialize)]
+pub struct IcrcTransactionWithId {
+    pub id: Nat,
+    pub transaction: IcrcTransaction,
+}
+
+#[derive(CandidType, Deserialize)]
+pub struct IcrcGetTransactions {
+    pub balance: Nat,
+    pub transactions: Vec<IcrcTransactionWithId>,
+    pub oldest_tx_id: Option<Nat>,
+}
+
+#[derive(CandidType, Deserialize)]
+pub struct IcrcGetTransactionsErr {
+    pub message: String,
+}
+
+#[derive(CandidType, Deserialize)]
+pub enum IcrcGetTransactionsResult {
+    Ok(IcrcGetTransactions),
+    Err(IcrcGetTransactionsErr),
+}
+
+///
+/// TokenLedger
+///
+/// An ICRC-1 ledger with the index canister serving its account histories, if any.
+///
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TokenLedger {
+    pub symbol: String,
+    pub decimals: u8,
+    pub ledger: String,
+    pub index: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub price_icp: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub price_usd: Option<f64>,
+}
+
+///
+/// TokenTransfer
+///
+/// Amounts are in the token's base units; `from` is empty for mints and `to` for burns.
+/// Values use the ICPSwap snapshot prices, not the price at the time of the transfer.
+/// Approvals move no tokens and have no TokenTransfer.
+///
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TokenTransfer {
+    pub kind: String,
+    pub id: u64,
+    pub from: Option<String>,
+    pub to: Option<String>,
+    pub amount: u128,
+    pub fee: Option<u128>,
+    pub memo: Option<String>,
+    pub timestamp_nanos: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value_icp: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value_usd: Option<f64>,
+}
+
+impl TokenTransfer {
+    // new
+    // a transfer without fee, memo or values, shared by the index and ICRC-3 paths
+    pub fn new(
+        kind: &str,
+        id: u64,
+        from: Option<&Account>,
+        to: Option<&Account>,
+        amount: u128,
+        timestamp_nanos: u64,
+    ) -> Self {
+        Self {
+            kind: kind.to_string(),
+            id,
+            from: from.map(Account::to_address),
+            to: to.map(Account::to_address),
+            amount,
+            fee: None,
+            memo: None,
+            timestamp_nanos,
+            value_icp: None,
+            value_usd: None,
+        }
+    }
+
+    // from_transaction
+    // an index canister transaction, None for approvals
+    pub fn from_transaction(tx: &IcrcTransactionWithId) -> Option<Self> {
+        let t = &tx.transaction;
+        let (from, to, amount, fee, memo) = if let Some(mint) = &t.mint {
+            (None, Some(&mint.to), &mint.amount, None, &mint.memo)
+        } else if let Some(burn) = &t.burn {
+            (Some(&burn.from), None, &burn.amount, None, &burn.memo)
+        } else if let Some(transfer) = &t.transfer {
+            (Some(&transfer.from), Some(&transfer.to), &transfer.amount, transfer.fee.as_ref(), &transfer.memo)
+        } else {
+            return None;
+        };
+
+        Some(Self {
+            fee: fee.and_then(nat_to_u128),
+            memo: memo.as_ref().map(hex::encode),
+            ..Self::new(&t.kind, nat_to_u128(&tx.id)? as u64, from, to, nat_to_u128(amount)?, t.timestamp)
+        })
+    }
+}
+
+///
+/// TokenAccountJson
+///
+
+#[derive(Debug, Serialize)]
+pub struct TokenAccountJson {
+    pub name: String,
+    pub address: String,
+    pub ty: crate::Type,
+    pub balance: Option<u128>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub balance_icp: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub balance_usd: Option<f64>,
+    pub transfers: Vec<TokenTransfer>,
+}
+
+///
+/// TokenHistoryJson
+///
+
+#[derive(Debug, Serialize)]
+pub struct TokenHistoryJson {
+    pub token: TokenLedger,
+    pub accounts: Vec<TokenAccountJson>,
+}
+
+impl TokenHistoryJson {
+    // apply_prices
+    // values the token, balances and transfers in ICP and USD from the snapshot
+    pub fn apply_prices(&mut self, icpswap: &IcpSwapSnapshot) {
+        let ledger = self.token.ledger.as_str();
+        self.token.price_icp = icpswap.price_in_icp(ledger);
+        self.token.price_usd = icpswap.value_in_usd(ledger, 1.0);
+
+        let scale = 10f64.powi(i32::from(self.token.decimals));
+        let value = |amount: u128| {
+            let whole = amount as f64 / scale;
+            (icpswap.value_in_icp(ledger, whole), icpswap.value_in_usd(ledger, whole))
+        };
+        for account in &mut self.accounts {
+            (account.balance_icp, account.balance_usd) = account.balance.map_or((None, None), value);
+            for tx in &mut account.transfers {
+                (tx.value_icp, tx.value_usd) = value(tx.amount);
+            }
+        }
+    }
+}
+
+// nat_to_u128
+pub fn nat_to_u128(nat: &Nat) -> Option<u128> {
+    nat.0.to_string().parse().ok()
+}
+
+// token_ledger
+// symbol and decimals come from the ledger itself
+pub async fn token_ledger<B: CanisterBackend>(
+    backend: &B,
+    ledger: Principal,
+    index: Option<Principal>,
+) -> Result<TokenLedger, Box<dyn std::error::Error>> {
+    let symbol = backend.query_bytes(ledger, "icrc1_symbol", Encode!()?).await?;
+    let decimals = backend.query_bytes(ledger, "icrc1_decimals", Encode!()?).await?;
+
+    Ok(TokenLedger {
+        symbol: Decode!(&symbol, String)?,
+        decimals: Decode!(&decimals, u8)?,
+        ledger: ledger.to_text(),
+        index: index.map(|index| index.to_text()),
+        price_icp: None,
+        price_usd: None,
+    })
+}
+
+// fetch_icrc_transactions
+// one page of the account's history, newest first, from `start` down
+pub async fn fetch_icrc_transactions<B: CanisterBackend>(
+    backend: &B,
+    index: Principal,
+    account: Account,
+    start: Option<Nat>,
+) -> Result<IcrcGetTransactions, Box<dyn std::error::Error>> {
+    let request = GetIcrcAccountTransactionsArgs { account, start, max_results: Nat::from(ICRC_INDEX_MAX_RESULTS) };
+    let response = backend.query_bytes(index, "get_account_transactions", Encode!(&request)?).await?;
+
+    match Decode!(&response, IcrcGetTransactionsResult)? {
+        IcrcGetTransactionsResult::Ok(resp) => Ok(resp),
+        IcrcGetTransactionsResult::Err(err) => Err(err.message.into()),
+    }
+}
+
+// fetch_icrc_history
+// Pages through the account's whole history, each page starting at the oldest
+// id seen so far, until the index's oldest_tx_id or an empty page.
+pub async fn fetch_icrc_history<B: CanisterBackend>(
+    backend: &B,
+    index: Principal,
+    account: Account,
+) -> Result<IcrcGetTransactions, Box<dyn std::error::Error>> {
+    let mut history = fetch_icrc_transactions(backend, index, account.clone(), None).await?;
+
+    while let Some(last) = history.transactions.last().map(|tx| tx.id.clone()) {
+        if history.oldest_tx_id.as_ref().is_none_or(|oldest| *oldest >= last) {
+            break;
+        }
+        let page = fetch_icrc_transactions(backend, index, account.clone(), Some(last.clone())).await?;
+        let count = history.transactions.len();
+        history.transactions.extend(page.transactions.into_iter().filter(|tx| tx.id < last));
+        if history.transactions.len() == count {
+            break;
+        }
+    }
+
+    Ok(history)
+}
+
+// fetch_token_history
+// every address book principal with transfers or a balance on the ledger
+pub async fn fetch_token_history<B: CanisterBackend>(
+    backend: &B,
+    token: TokenLedger,
+    entries: &[AccountData],
+    date_filter: Option<DateRangeFilter>,
+) -> Result<TokenHistoryJson, Box<dyn std::error::Error>> {
+    let index = token.index.as_deref().ok_or_else(|| format!("{} has no index canister", token.symbol))?;
+    let index = Principal::from_text(index)?;
+    let mut accounts = Vec::new();
+
+    for entry in entries {
+        let Some(account) = Account::from_entry(entry) else { continue };
+        let address = account.to_address();
+        println!("Fetching {} txs for {}", token.symbol, address);
+
+        let response = match fetch_icrc_history(backend, index, account).await {
+            Ok(response) => response,
+            Err(e) => {
+                eprintln!("Error fetching {} transactions for {}: {}", token.symbol, address, e);
+                continue;
+            }
+        };
+        let transfers: Vec<TokenTransfer> = response
+            .transactions
+            .iter()
+            .filter(|tx| date_filter.is_none_or(|filter| filter.contains(tx.transaction.timestamp / 1_000_000_000)))
+            .filter_map(TokenTransfer::from_transaction)
+            .collect();
+        let balance = nat_to_u128(&response.balance).unwrap_or(0);
+        if transfers.is_empty() && balance == 0 {
+            continue;
+        }
+
+        accounts.push(TokenAccountJson {
+            name: entry.name.clone(),
+            address,
+            ty: entry.ty,
+            balance: Some(balance),
+            balance_icp: None,
+            balance_usd: None,
+            transfers,
+        });
+    }
+
+    Ok(TokenHistoryJson { token, accounts })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{canister::MemoryBackend, icpswap::ICP_LEDGER_ID, Type};
+
+    const TOKEN_LEDGER: &str = "mxzaz-hqaaa-aaaar-qaada-cai";
+
+    fn ticker(id: &str, base: (&str, &str), target: (&str, &str), last_price: &str) -> String {
+        format!(
+            r#"{{"ticker_id": "{id}", "ticker_name": "{}_{}", "base_id": "{}", "base_currency": "{}",
+                "target_id": "{}", "target_currency": "{}", "last_price": "{last_price}",
+                "base_volume": "0", "target_volume": "0", "base_volume_24H": "0", "target_volume_24H": "0",
+                "total_volume_usd": "0", "volume_usd_24H": "0", "fee_usd": "0", "liquidity_in_usd": "1000"}}"#,
+            base.1, target.1, base.0, base.1, target.0, target.1
+        )
+    }
+
+    fn transfer(amount: u128) -> TokenTransfer {
+        TokenTransfer {
+            kind: "transfer".to_string(),
+            id: 1,
+            from: None,
+            to: None,
+            amount,
+            fee: None,
+            memo: None,
+            timestamp_nanos: 0,
+            value_icp: None,
+            value_usd: None,
+        }
+    }
+
+    #[test]
+    fn apply_prices_values_balances_and_transfers() {
+        // one TOKEN costs 0.5 ICP, one ICP costs 10 ckUSDC
+        let json = format!(
+            "[{}, {}]",
+            ticker("lqt4r-dqaaa-aaaag-qdjpq-cai", (ICP_LEDGER_ID, "ICP"), (TOKEN_LEDGER, "TOKEN"), "0.5"),
+            ticker(
+                "xmiu5-jqaaa-aaaag-qbz7q-cai",
+                ("xevnm-gaaaa-aaaar-qafnq-cai", "ckUSDC"),
+                (ICP_LEDGER_ID, "ICP"),
+                "10"
+            )
+        );
+        let icpswap = IcpSwapSnapshot::from_json(&json).unwrap();
+        let mut history = TokenHistoryJson {
+            token: TokenLedger {
+                symbol: "TOKEN".to_string(),
+                decimals: 8,
+                ledger: TOKEN_LEDGER.to_string(),
+                index: None,
+                price_icp: None,
+                price_usd: None,
+            },
+            accounts: vec![TokenAccountJson {
+                name: "Holder".to_string(),
+                address: "aaaaa-aa".to_string(),
+                ty: Type::Identified,
+                balance: Some(200_000_000),
+                balance_icp: None,
+                balance_usd: None,
+                transfers: vec![transfer(50_000_000)],
+            }],
+        };
+
+        history.apply_prices(&icpswap);
+
+        assert_eq!(history.token.price_icp, Some(0.5));
+        assert_eq!(history.token.price_usd, Some(5.0));
+        let account = &history.accounts[0];
+        assert_eq!((account.balance_icp, account.balance_usd), (Some(1.0), Some(10.0)));
+        assert_eq!((account.transfers[0].value_icp, account.transfers[0].value_usd), (Some(0.25), Some(2.5)));
+    }
+
+    #[test]
+    fn apply_prices_leaves_unpriced_tokens_empty() {
+        let icpswap = IcpSwapSnapshot::from_json("[]").unwrap();
+        let mut history = TokenHistoryJson {
+            token: TokenLedger {
+                symbol: "TOKEN".to_string(),
+                decimals: 8,
+                ledger: TOKEN_LEDGER.to_string(),
+                index: None,
+                price_icp: None,
+                price_usd: None,
+            },
+            accounts: Vec::new(),
+        };
+
+        history.apply_prices(&icpswap);
+
+        assert_eq!(history.token.price_icp, None);
+        assert_eq!(history.token.price_usd, None);
+    }
+
+    const INDEX: &str = "n5wcd-faaaa-aaaar-qaaea-cai";
+
+    fn account() -> Account {
+        Account { owner: Principal::from_text(TOKEN_LEDGER).unwrap(), subaccount: None }
+    }
+
+    fn page(ids: &[u64], oldest_tx_id: u64) -> IcrcGetTransactionsResult {
+        let transactions = ids
+            .iter()
+            .map(|id| IcrcTransactionWithId {
+                id: Nat::from(*id),
+                transaction: IcrcTransaction {
+                    kind: "mint".to_string(),
+                    mint: Some(IcrcMint { to: account(), amount: Nat::from(100u64), memo: None }),
+                    burn: None,
+                    transfer: None,
+                    approve: None,
+                    timestamp: 0,
+                },
+            })
+            .collect();
+        IcrcGetTransactionsResult::Ok(IcrcGetTransactions {
+            balance: Nat::from(900u64),
+            transactions,
+            oldest_tx_id: Some(Nat::from(oldest_tx_id)),
+        })
+    }
+
+    fn backend(pages: &[(Option<u64>, &[u64])], oldest_tx_id: u64) -> MemoryBackend {
+        let mut backend = MemoryBackend::default();
+        for (start, ids) in pages {
+            let request = GetIcrcAccountTransactionsArgs {
+                account: account(),
+                start: start.map(Nat::from),
+                max_results: Nat::from(ICRC_INDEX_MAX_RESULTS),
+            };
+            backend.respond(
+                Principal::from_text(INDEX).unwrap(),
+                "get_account_transactions",
+                Encode!(&request).unwrap(),
+                Encode!(&page(ids, oldest_tx_id)).unwrap(),
+            );
+        }
+        backend
+    }
+
+    async fn history_ids(backend: &MemoryBackend) -> Vec<u64> {
+        let history = fetch_icrc_history(backend, Principal::from_text(INDEX).unwrap(), account()).await.unwrap();
+        history.transactions.iter().map(|tx| nat_to_u128(&tx.id).unwrap() as u64).collect()
+    }
+
+    #[tokio::test]
+    async fn fetch_icrc_history_pages_down_to_the_oldest_transaction() {
+        let backend = backend(&[(None, &[9, 8, 7]), (Some(7), &[7, 6, 5]), (Some(5), &[4, 3, 2, 1])], 1);
+
+        assert_eq!(history_ids(&backend).await, [9, 8, 7, 6, 5, 4, 3, 2, 1]);
+    }
+
+    #[tokio::test]
+    async fn fetch_icrc_history_stops_on_a_page_without_older_transactions() {
+        let backend = backend(&[(None, &[9, 8]), (Some(8), &[8])], 1);
+
+        assert_eq!(history_ids(&backend).await, [9, 8]);
+    }
+}
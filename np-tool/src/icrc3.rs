@@ -0,0 +1,527 @@
+use crate::{
+    canister::CanisterBackend,
+    icrc::{nat_to_u128, TokenAccountJson, TokenHistoryJson, TokenLedger, TokenTransfer},
+    ledger::BLOCKS_PER_CALL,
+    transactions::{Account, DateRangeFilter},
+    AccountData,
+};
+use candid::{CandidType, Decode, Encode, Int, Nat, Principal};
+use serde::Deserialize;
+use std::collections::HashMap;
+
+///
+/// Value
+///
+/// The ICRC-3 generic value every block is encoded in.
+///
+
+#[derive(Debug, Clone, CandidType, Deserialize)]
+pub enum Value {
+    Blob(serde_bytes::ByteBuf),
+    Text(String),
+    Nat(Nat),
+    Int(Int),
+    Array(Vec<Value>),
+    Map(Vec<(String, Value)>),
+}
+
+impl Value {
+    fn as_map(&self) -> Option<&[(String, Value)]> {
+        match self {
+            Value::Map(map) => Some(map),
+            _ => None,
+        }
+    }
+
+    fn as_text(&self) -> Option<&str> {
+        match self {
+            Value::Text(text) => Some(text),
+            _ => None,
+        }
+    }
+
+    fn as_nat(&self) -> Option<u128> {
+        match self {
+            Value::Nat(nat) => nat_to_u128(nat),
+            _ => None,
+        }
+    }
+
+    fn as_blob(&self) -> Option<&[u8]> {
+        match self {
+            Value::Blob(blob) => Some(blob),
+            _ => None,
+        }
+    }
+
+    // as_account
+    // an array of the owner and, optionally, the subaccount
+    fn as_account(&self) -> Option<Account> {
+        let Value::Array(parts) = self else { return None };
+        let owner = Principal::try_from_slice(parts.first()?.as_blob()?).ok()?;
+        let subaccount = match parts.get(1) {
+            Some(sub) => Some(serde_bytes::ByteBuf::from(sub.as_blob()?.to_vec())),
+            None => None,
+        };
+
+        Some(Account { owner, subaccount })
+    }
+}
+
+#[derive(CandidType, Deserialize)]
+pub struct GetBlocksRequest {
+    pub start: Nat,
+    pub length: Nat,
+}
+
+#[derive(CandidType, Deserialize)]
+pub struct BlockWithId {
+    pub id: Nat,
+    pub block: Value,
+}
+
+candid::define_function!(pub GetBlocksCallback : (Vec<GetBlocksRequest>) -> (GetBlocksResult) query);
+
+#[derive(CandidType, Deserialize)]
+pub struct ArchivedBlocks {
+    pub args: Vec<GetBlocksRequest>,
+    pub callback: GetBlocksCallback,
+}
+
+#[derive(CandidType, Deserialize)]
+pub struct GetBlocksResult {
+    pub log_length: Nat,
+    pub blocks: Vec<BlockWithId>,
+    pub archived_blocks: Vec<ArchivedBlocks>,
+}
+
+///
+/// Icrc3Operation
+///
+
+#[derive(Debug, Clone)]
+pub enum Icrc3Operation {
+    Mint { to: Account, amount: u128 },
+    Burn { from: Account, amount: u128, spender: Option<Account> },
+    Transfer { from: Account, to: Account, amount: u128, spender: Option<Account> },
+    Approve { from: Account, spender: Account, amount: u128, expected_allowance: Option<u128>, expires_at: Option<u64> },
+}
+
+///
+/// Icrc3Block
+///
+/// A decoded block; the fee is the transaction fee or, failing that, the block fee.
+///
+
+#[derive(Debug, Clone)]
+pub struct Icrc3Block {
+    pub id: u64,
+    pub timestamp_nanos: u64,
+    pub operation: Icrc3Operation,
+    pub fee: Option<u128>,
+    pub memo: Option<Vec<u8>>,
+}
+
+impl Icrc3Block {
+    // kind
+    // named as the ICRC index canister names transactions
+    pub fn kind(&self) -> &'static str {
+        match self.operation {
+            Icrc3Operation::Mint { .. } => "mint",
+            Icrc3Operation::Burn { .. } => "burn",
+            Icrc3Operation::Transfer { .. } => "transfer",
+            Icrc3Operation::Approve { .. } => "approve",
+        }
+    }
+
+    // to_token_transfer
+    // None for approvals
+    pub fn to_token_transfer(&self) -> Option<TokenTransfer> {
+        let (from, to, amount) = match &self.operation {
+            Icrc3Operation::Mint { to, amount } => (None, Some(to), *amount),
+            Icrc3Operation::Burn { from, amount, .. } => (Some(from), None, *amount),
+            Icrc3Operation::Transfer { from, to, amount, .. } => (Some(from), Some(to), *amount),
+            Icrc3Operation::Approve { .. } => return None,
+        };
+
+        Some(TokenTransfer {
+            fee: self.fee,
+            memo: self.memo.as_ref().map(hex::encode),
+            ..TokenTransfer::new(self.kind(), self.id, from, to, amount, self.timestamp_nanos)
+        })
+    }
+}
+
+// decode_block
+// Blocks carry their type in `btype`, or in `tx.op` for ledgers that predate it:
+// 1mint/mint, 1burn/burn, 1xfer/2xfer/xfer and 2approve/approve.
+pub fn decode_block(id: u64, block: &Value) -> Result<Icrc3Block, String> {
+    let block_map = block.as_map().ok_or_else(|| format!("block {id} is not a map"))?;
+    let tx = field(block_map, "tx").and_then(Value::as_map).ok_or_else(|| format!("block {id} has no tx"))?;
+    let btype = match field(block_map, "btype").and_then(Value::as_text) {
+        Some(btype) => btype,
+        None => field(tx, "op").and_then(Value::as_text).ok_or_else(|| format!("block {id} has no btype or op"))?,
+    };
+    let kind = btype.strip_prefix(['1', '2']).unwrap_or(btype);
+
+    let account = |key| field(tx, key).and_then(Value::as_account);
+    let required = |key| account(key).ok_or_else(|| format!("block {id} has no {key} account"));
+    let amount = field(tx, "amt").and_then(Value::as_nat).ok_or_else(|| format!("block {id} has no amount"));
+    let operation = match kind {
+        "mint" => Icrc3Operation::Mint { to: required("to")?, amount: amount? },
+        "burn" => Icrc3Operation::Burn { from: required("from")?, amount: amount?, spender: account("spender") },
+        "xfer" => Icrc3Operation::Transfer {
+            from: required("from")?,
+            to: required("to")?,
+            amount: amount?,
+            spender: account("spender"),
+        },
+        "approve" => Icrc3Operation::Approve {
+            from: required("from")?,
+            spender: required("spender")?,
+            amount: amount?,
+            expected_allowance: field(tx, "expected_allowance").and_then(Value::as_nat),
+            expires_at: field(tx, "expires_at").and_then(Value::as_nat).map(|nanos| nanos as u64),
+        },
+        _ => return Err(format!("block {id} has unknown type {btype}")),
+    };
+
+    Ok(Icrc3Block {
+        id,
+        timestamp_nanos: field(block_map, "ts").and_then(Value::as_nat).unwrap_or(0) as u64,
+        operation,
+        fee: field(tx, "fee").or_else(|| field(block_map, "fee")).and_then(Value::as_nat),
+        memo: field(tx, "memo").and_then(Value::as_blob).map(<[u8]>::to_vec),
+    })
+}
+
+fn field<'a>(map: &'a [(String, Value)], key: &str) -> Option<&'a Value> {
+    map.iter().find(|(k, _)| k == key).map(|(_, value)| value)
+}
+
+// get_blocks
+async fn get_blocks<B: CanisterBackend>(
+    backend: &B,
+    canister_id: Principal,
+    method: &str,
+    start: u64,
+    length: u64,
+) -> Result<GetBlocksResult, Box<dyn std::error::Error>> {
+    let request = vec![GetBlocksRequest { start: Nat::from(start), length: Nat::from(length) }];
+    let response = backend.query_bytes(canister_id, method, Encode!(&request)?).await?;
+
+    Ok(Decode!(&response, GetBlocksResult)?)
+}
+
+// log_length
+pub async fn log_length<B: CanisterBackend>(backend: &B, ledger: Principal) -> Result<u64, Box<dyn std::error::Error>> {
+    let result = get_blocks(backend, ledger, "icrc3_get_blocks", 0, 0).await?;
+
+    Ok(nat_to_u128(&result.log_length).unwrap_or(0) as u64)
+}
+
+// fetch_blocks
+// Every block in [start, end), following the ledger to its archives for blocks
+// it no longer holds. Both cap the blocks per call, so ask again from the
+// highest block received until the range is covered.
+pub async fn fetch_blocks<B: CanisterBackend>(
+    backend: &B,
+    ledger: Principal,
+    start: u64,
+    end: u64,
+) -> Result<Vec<(u64, Value)>, Box<dyn std::error::Error>> {
+    let mut blocks = Vec::new();
+    let mut next = start;
+
+    while next < end {
+        let result = get_blocks(backend, ledger, "icrc3_get_blocks", next, (end - next).min(BLOCKS_PER_CALL)).await?;
+        let received = blocks.len();
+
+        for archived in result.archived_blocks {
+            let callback = archived.callback.0;
+            for args in archived.args {
+                let (Some(from), Some(length)) = (nat_to_u128(&args.start), nat_to_u128(&args.length)) else {
+                    continue;
+                };
+                blocks.extend(
+                    fetch_archived(backend, callback.principal, &callback.method, from as u64, length as u64).await?,
+                );
+            }
+        }
+        blocks.extend(with_ids(result.blocks));
+
+        match blocks[received..].iter().map(|(id, _)| *id).max() {
+            Some(highest) if highest >= next => next = highest + 1,
+            _ => break,
+        }
+    }
+    blocks.retain(|(id, _)| (start..end).contains(id));
+    blocks.sort_by_key(|(id, _)| *id);
+    blocks.dedup_by_key(|(id, _)| *id);
+
+    Ok(blocks)
+}
+
+// fetch_archived
+async fn fetch_archived<B: CanisterBackend>(
+    backend: &B,
+    archive: Principal,
+    method: &str,
+    start: u64,
+    length: u64,
+) -> Result<Vec<(u64, Value)>, Box<dyn std::error::Error>> {
+    let mut blocks = Vec::new();
+    let (mut next, end) = (start, start + length);
+
+    while next < end {
+        println!("Fetching blocks {}..{} from archive {}", next, end, archive);
+        let result = get_blocks(backend, archive, method, next, (end - next).min(BLOCKS_PER_CALL)).await?;
+        let received = with_ids(result.blocks);
+        match received.iter().map(|(id, _)| *id).max() {
+            Some(highest) if highest >= next => next = highest + 1,
+            _ => break,
+        }
+        blocks.extend(received);
+    }
+
+    Ok(blocks)
+}
+
+fn with_ids(blocks: Vec<BlockWithId>) -> Vec<(u64, Value)> {
+    blocks.into_iter().filter_map(|b| Some((nat_to_u128(&b.id)? as u64, b.block))).collect()
+}
+
+// balance_of
+async fn balance_of<B: CanisterBackend>(
+    backend: &B,
+    ledger: Principal,
+    account: &Account,
+) -> Result<u128, Box<dyn std::error::Error>> {
+    let response = backend.query_bytes(ledger, "icrc1_balance_of", Encode!(account)?).await?;
+
+    Ok(nat_to_u128(&Decode!(&response, Nat)?).unwrap_or(0))
+}
+
+// fetch_token_history_from_blocks
+// The same history as the index canister gives, built from the ledger's own
+// block log over [start, end) for address book principals.
+pub async fn fetch_token_history_from_blocks<B: CanisterBackend>(
+    backend: &B,
+    token: TokenLedger,
+    entries: &[AccountData],
+    start: u64,
+    end: u64,
+    date_filter: Option<DateRangeFilter>,
+) -> Result<TokenHistoryJson, Box<dyn std::error::Error>> {
+    let ledger = Principal::from_text(&token.ledger)?;
+    let labelled: HashMap<String, (&AccountData, Account)> = entries
+        .iter()
+        .filter_map(|entry| {
+            let account = Account::from_entry(entry)?;
+            Some((account.to_address(), (entry, account)))
+        })
+        .collect();
+
+    println!("Fetching {} blocks {}..{}", token.symbol, start, end);
+    let mut transfers: HashMap<&str, Vec<TokenTransfer>> = HashMap::new();
+    for (id, block) in fetch_blocks(backend, ledger, start, end).await? {
+        let block = match decode_block(id, &block) {
+            Ok(block) => block,
+            Err(e) => {
+                eprintln!("Error decoding {} block: {}", token.symbol, e);
+                continue;
+            }
+        };
+        if date_filter.is_some_and(|filter| !filter.contains(block.timestamp_nanos / 1_000_000_000)) {
+            continue;
+        }
+        let Some(transfer) = block.to_token_transfer() else { continue };
+
+        for address in transfer.from.iter().chain(&transfer.to) {
+            if let Some((key, _)) = labelled.get_key_value(address) {
+                transfers.entry(key.as_str()).or_default().push(transfer.clone());
+            }
+        }
+    }
+
+    let mut accounts = Vec::new();
+    for (address, mut account_transfers) in transfers {
+        let (entry, account) = &labelled[address];
+        account_transfers.sort_by_key(|tx| std::cmp::Reverse(tx.id));
+        account_transfers.dedup_by_key(|tx| tx.id);
+        let balance = balance_of(backend, ledger, account)
+            .await
+            .map_err(|e| eprintln!("Error fetching {} balance of {}: {}", token.symbol, address, e))
+            .ok();
+
+        accounts.push(TokenAccountJson {
+            name: entry.name.clone(),
+            address: address.to_string(),
+            ty: entry.ty,
+            balance,
+            balance_icp: None,
+            balance_usd: None,
+            transfers: account_transfers,
+        });
+    }
+    accounts.sort_by(|a, b| a.name.cmp(&b.name));
+
+    Ok(TokenHistoryJson { token, accounts })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::canister::MemoryBackend;
+
+    const OWNER: &str = "mxzaz-hqaaa-aaaar-qaada-cai";
+    const OTHER: &str = "n5wcd-faaaa-aaaar-qaaea-cai";
+
+    fn text(value: &str) -> Value {
+        Value::Text(value.to_string())
+    }
+
+    fn nat(value: u64) -> Value {
+        Value::Nat(Nat::from(value))
+    }
+
+    fn map(fields: Vec<(&str, Value)>) -> Value {
+        Value::Map(fields.into_iter().map(|(key, value)| (key.to_string(), value)).collect())
+    }
+
+    fn account(owner: &str, subaccount: Option<[u8; 32]>) -> Value {
+        let owner = Value::Blob(serde_bytes::ByteBuf::from(Principal::from_text(owner).unwrap().as_slice().to_vec()));
+        let subaccount = subaccount.map(|sub| Value::Blob(serde_bytes::ByteBuf::from(sub.to_vec())));
+        Value::Array(std::iter::once(owner).chain(subaccount).collect())
+    }
+
+    fn address(owner: &str, subaccount: Option<[u8; 32]>) -> String {
+        Account { owner: Principal::from_text(owner).unwrap(), subaccount: subaccount.map(|sub| sub.to_vec().into()) }
+            .to_address()
+    }
+
+    fn block(btype: Option<&str>, tx: Vec<(&str, Value)>, block_fee: Option<u64>) -> Value {
+        let mut fields = vec![("ts", nat(1_700_000_000_000_000_000)), ("tx", map(tx))];
+        fields.extend(btype.map(|btype| ("btype", text(btype))));
+        fields.extend(block_fee.map(|fee| ("fee", nat(fee))));
+        map(fields)
+    }
+
+    // kind, from, to and amount of the decoded block, with accounts as addresses
+    fn summary(block: &Icrc3Block) -> (&'static str, Option<String>, Option<String>, u128) {
+        let (from, to, amount) = match &block.operation {
+            Icrc3Operation::Mint { to, amount } => (None, Some(to), *amount),
+            Icrc3Operation::Burn { from, amount, .. } => (Some(from), None, *amount),
+            Icrc3Operation::Transfer { from, to, amount, .. } => (Some(from), Some(to), *amount),
+            Icrc3Operation::Approve { from, spender, amount, .. } => (Some(from), Some(spender), *amount),
+        };
+        (block.kind(), from.map(Account::to_address), to.map(Account::to_address), amount)
+    }
+
+    #[test]
+    fn decode_block_operations() {
+        let sub = Some([7u8; 32]);
+        let cases = [
+            (Some("1mint"), vec![("to", account(OWNER, None))], ("mint", None, Some(address(OWNER, None)))),
+            (Some("1burn"), vec![("from", account(OWNER, sub))], ("burn", Some(address(OWNER, sub)), None)),
+            (
+                Some("1xfer"),
+                vec![("from", account(OWNER, None)), ("to", account(OTHER, sub))],
+                ("transfer", Some(address(OWNER, None)), Some(address(OTHER, sub))),
+            ),
+            (
+                Some("2xfer"),
+                vec![("from", account(OWNER, sub)), ("to", account(OTHER, None)), ("spender", account(OTHER, None))],
+                ("transfer", Some(address(OWNER, sub)), Some(address(OTHER, None))),
+            ),
+            (
+                Some("2approve"),
+                vec![("from", account(OWNER, None)), ("spender", account(OTHER, None))],
+                ("approve", Some(address(OWNER, None)), Some(address(OTHER, None))),
+            ),
+            // ledgers predating btype name the operation in tx.op
+            (None, vec![("op", text("mint")), ("to", account(OTHER, sub))], ("mint", None, Some(address(OTHER, sub)))),
+            (
+                None,
+                vec![("op", text("xfer")), ("from", account(OWNER, None)), ("to", account(OTHER, None))],
+                ("transfer", Some(address(OWNER, None)), Some(address(OTHER, None))),
+            ),
+            (
+                None,
+                vec![("op", text("burn")), ("from", account(OTHER, None))],
+                ("burn", Some(address(OTHER, None)), None),
+            ),
+        ];
+
+        for (i, (btype, mut tx, (kind, from, to))) in cases.into_iter().enumerate() {
+            tx.push(("amt", nat(500)));
+            let decoded = decode_block(i as u64, &block(btype, tx, None)).unwrap();
+
+            assert_eq!(summary(&decoded), (kind, from, to, 500), "{btype:?}");
+            assert_eq!(decoded.id, i as u64);
+            assert_eq!(decoded.timestamp_nanos, 1_700_000_000_000_000_000);
+        }
+    }
+
+    #[test]
+    fn decode_block_prefers_the_transaction_fee() {
+        let tx = |fee: Option<u64>| {
+            let mut tx = vec![("from", account(OWNER, None)), ("to", account(OTHER, None)), ("amt", nat(500))];
+            tx.extend(fee.map(|fee| ("fee", nat(fee))));
+            tx
+        };
+
+        let fee = |tx, block_fee| decode_block(1, &block(Some("1xfer"), tx, block_fee)).unwrap().fee;
+        assert_eq!(fee(tx(Some(20)), Some(10)), Some(20));
+        assert_eq!(fee(tx(None), Some(10)), Some(10));
+        assert_eq!(fee(tx(None), None), None);
+    }
+
+    #[test]
+    fn decode_block_rejects_unknown_and_incomplete_blocks() {
+        let transfer = || vec![("from", account(OWNER, None)), ("to", account(OTHER, None)), ("amt", nat(500))];
+
+        assert_eq!(
+            decode_block(3, &block(Some("107feecol"), transfer(), None)).unwrap_err(),
+            "block 3 has unknown type 107feecol"
+        );
+        assert!(decode_block(3, &block(None, transfer(), None)).is_err());
+        assert!(decode_block(3, &block(Some("1xfer"), vec![("from", account(OWNER, None))], None)).is_err());
+        assert!(decode_block(3, &text("not a block")).is_err());
+    }
+
+    fn blocks_result(ids: std::ops::Range<u64>, archived: Option<(&str, u64, u64)>) -> GetBlocksResult {
+        GetBlocksResult {
+            log_length: Nat::from(7u64),
+            blocks: ids.map(|id| BlockWithId { id: Nat::from(id), block: nat(id) }).collect(),
+            archived_blocks: archived
+                .map(|(archive, start, length)| ArchivedBlocks {
+                    args: vec![GetBlocksRequest { start: Nat::from(start), length: Nat::from(length) }],
+                    callback: GetBlocksCallback::new(
+                        Principal::from_text(archive).unwrap(),
+                        "icrc3_get_blocks".to_string(),
+                    ),
+                })
+                .into_iter()
+                .collect(),
+        }
+    }
+
+    #[tokio::test]
+    async fn fetch_blocks_joins_archived_and_ledger_blocks() {
+        let (ledger, archive) = (Principal::from_text(OWNER).unwrap(), Principal::from_text(OTHER).unwrap());
+        let mut backend = MemoryBackend::default();
+        let mut respond = |canister_id, start: u64, length: u64, result: GetBlocksResult| {
+            let request = vec![GetBlocksRequest { start: Nat::from(start), length: Nat::from(length) }];
+            backend.respond(canister_id, "icrc3_get_blocks", Encode!(&request).unwrap(), Encode!(&result).unwrap());
+        };
+        // the ledger holds 3..7 and points at the archive for 0..4, which answers in two pages
+        respond(ledger, 0, 7, blocks_result(3..7, Some((OTHER, 0, 4))));
+        respond(archive, 0, 4, blocks_result(0..3, None));
+        respond(archive, 3, 1, blocks_result(3..4, None));
+
+        let blocks = fetch_blocks(&backend, ledger, 0, 7).await.unwrap();
+
+        assert_eq!(blocks.iter().map(|(id, _)| *id).collect::<Vec<_>>(), [0, 1, 2, 3, 4, 5, 6]);
+    }
+}
@@ -0,0 +1,180 @@
+use crate::{
+    canister::CanisterBackend,
+    ledger::fetch_ledger_transactions,
+    transactions::{AccountTransactionsJson, SimplifiedTransfer},
+};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+
+// ids closer than this share one ledger fetch
+const BATCH_GAP: u64 = 100;
+
+const UNCERTIFIED_NOTE: &str = "Ledger blocks come from uncertified query calls; \
+    neither the certificate nor the hash chain between blocks was checked.";
+
+///
+/// IndexMismatch
+///
+/// A transfer the index canister returned that the ledger block disagrees with,
+/// or whose block could not be fetched.
+///
+
+#[derive(Debug, Serialize)]
+pub struct IndexMismatch {
+    pub id: u64,
+    pub names: Vec<String>,
+    pub differences: Vec<String>,
+}
+
+///
+/// IndexCheckReport
+///
+
+#[derive(Debug, Serialize)]
+pub struct IndexCheckReport {
+    pub note: &'static str,
+    pub transfer_count: usize,
+    pub checked: usize,
+    pub matched: usize,
+    pub mismatched: usize,
+    pub mismatches: Vec<IndexMismatch>,
+}
+
+// check_index
+// Fetches the ledger block of every transfer the index returned, or of `sample`
+// transfers spread evenly over the ids, and compares it field by field with the
+// index's copy. Histories scanned from the ledger are left out. A block without
+// a transfer, or one that could not be fetched, counts as a mismatch.
+pub async fn check_index<B: CanisterBackend>(
+    backend: &B,
+    results: &[AccountTransactionsJson],
+    sample: Option<usize>,
+) -> IndexCheckReport {
+    let mut transfers: BTreeMap<u64, (&SimplifiedTransfer, Vec<String>)> = BTreeMap::new();
+    for account_tx in results.iter().filter(|account_tx| account_tx.scanned_blocks.is_none()) {
+        for tx in &account_tx.transactions {
+            transfers.entry(tx.id).or_insert_with(|| (tx, Vec::new())).1.push(account_tx.name.clone());
+        }
+    }
+
+    let ids = sample_ids(transfers.keys().copied().collect(), sample);
+    let mut mismatches = Vec::new();
+    for batch in batches(&ids) {
+        let (start, end) = (batch[0], batch[batch.len() - 1] + 1);
+        println!("Checking {} transfers in blocks {}..{} against the ledger", batch.len(), start, end);
+        let blocks = match fetch_ledger_transactions(backend, start, end).await {
+            Ok(blocks) => blocks.into_iter().map(|tx| (tx.id, tx)).collect::<HashMap<_, _>>(),
+            Err(e) => {
+                mismatches.extend(batch.iter().map(|id| IndexMismatch {
+                    id: *id,
+                    names: transfers[id].1.clone(),
+                    differences: vec![format!("ledger block could not be fetched: {e}")],
+                }));
+                continue;
+            }
+        };
+
+        for id in batch {
+            let (index_tx, names) = &transfers[id];
+            let differences = match blocks.get(id).and_then(SimplifiedTransfer::from_transaction) {
+                Some(ledger_tx) => differences(index_tx, &ledger_tx),
+                None => vec!["ledger block is not a transfer".to_string()],
+            };
+            if !differences.is_empty() {
+                mismatches.push(IndexMismatch { id: *id, names: names.clone(), differences });
+            }
+        }
+    }
+
+    IndexCheckReport {
+        note: UNCERTIFIED_NOTE,
+        transfer_count: transfers.len(),
+        checked: ids.len(),
+        matched: ids.len() - mismatches.len(),
+        mismatched: mismatches.len(),
+        mismatches,
+    }
+}
+
+// sample_ids
+// `sample` ids spread evenly over the sorted ids, or all of them
+fn sample_ids(ids: Vec<u64>, sample: Option<usize>) -> Vec<u64> {
+    match sample {
+        Some(n) if n < ids.len() => (0..n).map(|i| ids[i * ids.len() / n]).collect(),
+        _ => ids,
+    }
+}
+
+// batches
+// runs of sorted ids less than BATCH_GAP apart
+fn batches(ids: &[u64]) -> Vec<&[u64]> {
+    let mut batches = Vec::new();
+    let mut first = 0;
+    for i in 1..=ids.len() {
+        if i == ids.len() || ids[i] - ids[i - 1] >= BATCH_GAP {
+            batches.push(&ids[first..i]);
+            first = i;
+        }
+    }
+
+    batches
+}
+
+// differences
+// one "field: index X, ledger Y" line per field that differs
+fn differences(index_tx: &SimplifiedTransfer, ledger_tx: &SimplifiedTransfer) -> Vec<String> {
+    let fields = [
+        ("op_type", index_tx.op_type.clone(), ledger_tx.op_type.clone()),
+        ("from", index_tx.from.clone(), ledger_tx.from.clone()),
+        ("to", index_tx.to.clone(), ledger_tx.to.clone()),
+        ("amount_e8s", index_tx.amount_e8s.to_string(), ledger_tx.amount_e8s.to_string()),
+        ("fee_e8s", index_tx.fee_e8s.to_string(), ledger_tx.fee_e8s.to_string()),
+        ("memo", index_tx.memo.to_string(), ledger_tx.memo.to_string()),
+        ("icrc1_memo", format!("{:?}", index_tx.icrc1_memo), format!("{:?}", ledger_tx.icrc1_memo)),
+        ("timestamp_nanos", format!("{:?}", index_tx.timestamp_nanos), format!("{:?}", ledger_tx.timestamp_nanos)),
+    ];
+
+    fields
+        .into_iter()
+        .filter(|(_, index, ledger)| index != ledger)
+        .map(|(field, index, ledger)| format!("{field}: index {index}, ledger {ledger}"))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn transfer(amount_e8s: u64, memo: u64) -> SimplifiedTransfer {
+        SimplifiedTransfer { memo, timestamp_nanos: Some(5), ..SimplifiedTransfer::transfer(1, "aa", "bb", amount_e8s) }
+    }
+
+    #[test]
+    fn differences_list_each_differing_field() {
+        assert!(differences(&transfer(100, 7), &transfer(100, 7)).is_empty());
+        assert_eq!(
+            differences(&transfer(100, 7), &transfer(90, 8)),
+            ["amount_e8s: index 100, ledger 90", "memo: index 7, ledger 8"]
+        );
+    }
+
+    #[test]
+    fn sample_ids_spread_over_the_range() {
+        let ids: Vec<u64> = (0..10).collect();
+
+        assert_eq!(sample_ids(ids.clone(), Some(5)), [0, 2, 4, 6, 8]);
+        assert_eq!(sample_ids(ids.clone(), Some(3)), [0, 3, 6]);
+        assert_eq!(sample_ids(ids.clone(), Some(10)), ids);
+        assert_eq!(sample_ids(ids.clone(), Some(50)), ids);
+        assert_eq!(sample_ids(ids.clone(), None), ids);
+        assert!(sample_ids(ids, Some(0)).is_empty());
+    }
+
+    #[test]
+    fn batches_split_at_large_gaps() {
+        let ids = [1, 2, 3, 50, 500, 501, 10_000];
+
+        assert_eq!(batches(&ids), [&[1, 2, 3, 50][..], &[500, 501], &[10_000]]);
+        assert!(batches(&[]).is_empty());
+    }
+}
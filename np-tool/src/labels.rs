@@ -0,0 +1,129 @@
+use crate::{
+    entities::entity_name,
+    neurons::NEURON_STAKE_LABEL,
+    transactions::{AccountTransactionsJson, RewardRecord, SimplifiedTransfer},
+    AccountData, Type,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+///
+/// Label
+///
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Label {
+    pub name: String,
+    pub ty: Type,
+}
+
+///
+/// LabelIndex
+///
+/// Reverse lookup from hex account id to the entity that owns it.
+/// The first label inserted for an account wins, so hand-maintained
+/// entries should go in before derived ones.
+///
+
+#[derive(Debug, Default)]
+pub struct LabelIndex {
+    labels: HashMap<String, Label>,
+}
+
+impl LabelIndex {
+    // from_entries
+    pub fn from_entries<'a>(entries: impl IntoIterator<Item = &'a AccountData>) -> Self {
+        let mut index = Self::default();
+        index.extend(entries);
+
+        index
+    }
+
+    // extend
+    pub fn extend<'a>(&mut self, entries: impl IntoIterator<Item = &'a AccountData>) {
+        for entry in entries {
+            if let Some(account) = entry.account_id() {
+                self.insert(account, Label { name: entry.name.clone(), ty: entry.ty });
+            }
+        }
+    }
+
+    // extend_from_results
+    // fetched accounts bring the reward accounts discovered from governance
+    pub fn extend_from_results(&mut self, results: &[AccountTransactionsJson]) {
+        for result in results {
+            let label = Label { name: result.name.clone(), ty: result.ty };
+            for account in result.account.iter().chain(&result.extra_accounts) {
+                self.insert(account.clone(), label.clone());
+            }
+        }
+    }
+
+    // extend_from_rewards
+    // reward accounts of address book principals, without fetching their histories
+    pub fn extend_from_rewards(&mut self, entries: &[AccountData], rewards: &[RewardRecord]) {
+        let by_principal: HashMap<String, Label> = entries
+            .iter()
+            .filter_map(|entry| Some((entry.principal?.to_text(), Label { name: entry.name.clone(), ty: entry.ty })))
+            .collect();
+
+        for reward in rewards {
+            let (Some(provider), Some(account)) = (&reward.node_provider, &reward.reward_account) else {
+                continue;
+            };
+            if let Some(label) = by_principal.get(provider) {
+                self.insert(account.clone(), label.clone());
+            }
+        }
+    }
+
+    // extend_neuron_stakes
+    pub fn extend_neuron_stakes(&mut self, accounts: impl IntoIterator<Item = String>) {
+        for account in accounts {
+            self.insert(account, Label { name: NEURON_STAKE_LABEL.to_string(), ty: Type::Neuron });
+        }
+    }
+
+    // roll_up
+    // renames every label to its entity, for entity level exports
+    pub fn roll_up(&mut self) {
+        for label in self.labels.values_mut() {
+            label.name = entity_name(&label.name);
+        }
+    }
+
+    // insert
+    pub fn insert(&mut self, account_id: String, label: Label) {
+        self.labels.entry(account_id).or_insert(label);
+    }
+
+    // get
+    pub fn get(&self, account_id: &str) -> Option<&Label> {
+        self.labels.get(account_id)
+    }
+
+    // iter
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &Label)> {
+        self.labels.iter()
+    }
+
+    // annotate
+    pub fn annotate(&self, transfer: &mut SimplifiedTransfer) {
+        transfer.from_label = self.get(&transfer.from).cloned();
+        transfer.to_label = self.get(&transfer.to).cloned();
+    }
+
+    // annotate_account
+    // labels both ends of every transfer and the counterparty as seen from the account
+    pub fn annotate_account(&self, account_tx: &mut AccountTransactionsJson) {
+        let AccountTransactionsJson { account, extra_accounts, transactions, .. } = account_tx;
+        let own: HashSet<&String> = account.iter().chain(extra_accounts.iter()).collect();
+
+        for tx in transactions {
+            self.annotate(tx);
+            let counterparty = if own.contains(&tx.from) { &tx.to } else { &tx.from };
+            tx.counterparty_label = self.get(counterparty).cloned();
+            tx.counterparty = Some(counterparty.clone());
+        }
+    }
+}
@@ -0,0 +1,390 @@
+use crate::{
+    canister::CanisterBackend,
+    transactions::{
+        AccountTransactionsJson, DateRangeFilter, Operation, ProviderRewardInfo, ScannedBlocks, SimplifiedTransfer,
+        TimeStamp, Tokens, Transaction, TransactionWithId,
+    },
+    AccountData,
+};
+use candid::{CandidType, Decode, Encode, Int, Principal};
+use serde::Deserialize;
+use std::collections::{HashMap, HashSet};
+
+const LEDGER_CANISTER_ID: &str = "ryjl3-tyaaa-aaaaa-aaaba-cai";
+
+// blocks asked for per call, within what the ICP ledger, ICRC-3 ledgers and their archives return
+pub const BLOCKS_PER_CALL: u64 = 1000;
+
+///
+/// TransactionSource
+///
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum TransactionSource {
+    /// Ask the index canister for each account's history
+    #[default]
+    Index,
+    /// Scan a range of ledger blocks instead of asking the index
+    Ledger,
+    /// Ask the index, and scan ledger blocks for the accounts it fails on
+    Fallback,
+}
+
+#[derive(CandidType, Deserialize)]
+pub struct GetBlocksArgs {
+    pub start: u64,
+    pub length: u64,
+}
+
+#[derive(CandidType, Deserialize)]
+pub struct AccountBalanceArgs {
+    pub account: serde_bytes::ByteBuf,
+}
+
+#[derive(CandidType, Deserialize)]
+pub enum LedgerOperation {
+    Mint {
+        to: serde_bytes::ByteBuf,
+        amount: Tokens,
+    },
+    Burn {
+        from: serde_bytes::ByteBuf,
+        spender: Option<serde_bytes::ByteBuf>,
+        amount: Tokens,
+    },
+    Transfer {
+        from: serde_bytes::ByteBuf,
+        to: serde_bytes::ByteBuf,
+        amount: Tokens,
+        fee: Tokens,
+        spender: Option<serde_bytes::ByteBuf>,
+    },
+    Approve {
+        from: serde_bytes::ByteBuf,
+        spender: serde_bytes::ByteBuf,
+        allowance_e8s: Int,
+        allowance: Tokens,
+        fee: Tokens,
+        expires_at: Option<TimeStamp>,
+        expected_allowance: Option<Tokens>,
+    },
+}
+
+#[derive(CandidType, Deserialize)]
+pub struct LedgerTransaction {
+    pub memo: u64,
+    pub icrc1_memo: Option<serde_bytes::ByteBuf>,
+    pub operation: Option<LedgerOperation>,
+    pub created_at_time: TimeStamp,
+}
+
+#[derive(CandidType, Deserialize)]
+pub struct LedgerBlock {
+    pub parent_hash: Option<serde_bytes::ByteBuf>,
+    pub transaction: LedgerTransaction,
+    pub timestamp: TimeStamp,
+}
+
+impl LedgerBlock {
+    // into_transaction
+    // the index canister's view of the block, with accounts in hex
+    pub fn into_transaction(self) -> Option<Transaction> {
+        let operation = match self.transaction.operation? {
+            LedgerOperation::Mint { to, amount } => Operation::Mint { to: hex::encode(to), amount },
+            LedgerOperation::Burn { from, spender, amount } => {
+                Operation::Burn { from: hex::encode(from), amount, spender: spender.map(hex::encode) }
+            }
+            LedgerOperation::Transfer { from, to, amount, fee, spender } => Operation::Transfer {
+                to: hex::encode(to),
+                fee,
+                from: hex::encode(from),
+                amount,
+                spender: spender.map(hex::encode),
+            },
+            LedgerOperation::Approve { from, spender, allowance, fee, expires_at, expected_allowance, .. } => {
+                Operation::Approve {
+                    fee,
+                    from: hex::encode(from),
+                    allowance,
+                    expected_allowance,
+                    expires_at,
+                    spender: hex::encode(spender),
+                }
+            }
+        };
+
+        Some(Transaction {
+            memo: self.transaction.memo,
+            icrc1_memo: self.transaction.icrc1_memo,
+            operation,
+            timestamp: Some(self.timestamp),
+            created_at_time: Some(self.transaction.created_at_time),
+        })
+    }
+}
+
+candid::define_function!(pub QueryArchiveFn : (GetBlocksArgs) -> (QueryArchiveResult) query);
+
+#[derive(CandidType, Deserialize)]
+pub struct ArchivedBlocksRange {
+    pub start: u64,
+    pub length: u64,
+    pub callback: QueryArchiveFn,
+}
+
+#[derive(CandidType, Deserialize)]
+pub struct QueryBlocksResponse {
+    pub chain_length: u64,
+    pub certificate: Option<serde_bytes::ByteBuf>,
+    pub blocks: Vec<LedgerBlock>,
+    pub first_block_index: u64,
+    pub archived_blocks: Vec<ArchivedBlocksRange>,
+}
+
+#[derive(CandidType, Deserialize)]
+pub struct BlockRange {
+    pub blocks: Vec<LedgerBlock>,
+}
+
+#[derive(CandidType, Deserialize, Debug)]
+pub enum QueryArchiveError {
+    BadFirstBlockIndex { requested_index: u64, first_valid_index: u64 },
+    Other { error_code: u64, error_message: String },
+}
+
+#[derive(CandidType, Deserialize)]
+pub enum QueryArchiveResult {
+    Ok(BlockRange),
+    Err(QueryArchiveError),
+}
+
+// query_blocks
+async fn query_blocks<B: CanisterBackend>(
+    backend: &B,
+    start: u64,
+    length: u64,
+) -> Result<QueryBlocksResponse, Box<dyn std::error::Error>> {
+    let ledger = Principal::from_text(LEDGER_CANISTER_ID)?;
+    let response = backend.query_bytes(ledger, "query_blocks", Encode!(&GetBlocksArgs { start, length })?).await?;
+
+    Ok(Decode!(&response, QueryBlocksResponse)?)
+}
+
+// chain_length
+pub async fn chain_length<B: CanisterBackend>(backend: &B) -> Result<u64, Box<dyn std::error::Error>> {
+    Ok(query_blocks(backend, 0, 0).await?.chain_length)
+}
+
+// fetch_ledger_transactions
+// Every block in [start, end) as the index canister would return it, asking
+// the archives for the blocks the ledger no longer holds. Blocks without an
+// operation are skipped.
+pub async fn fetch_ledger_transactions<B: CanisterBackend>(
+    backend: &B,
+    start: u64,
+    end: u64,
+) -> Result<Vec<TransactionWithId>, Box<dyn std::error::Error>> {
+    let mut transactions = Vec::new();
+    let mut next = start;
+
+    while next < end {
+        let response = query_blocks(backend, next, (end - next).min(BLOCKS_PER_CALL)).await?;
+        let mut highest = None;
+
+        for range in response.archived_blocks {
+            let archive_end = (range.start + range.length).min(end);
+            transactions.extend(fetch_archived(backend, &range.callback, range.start, archive_end).await?);
+            highest = highest.max(archive_end.checked_sub(1));
+        }
+        let first = response.first_block_index;
+        for (i, block) in response.blocks.into_iter().enumerate() {
+            let id = first + i as u64;
+            highest = highest.max(Some(id));
+            if let Some(transaction) = block.into_transaction() {
+                transactions.push(TransactionWithId { id, transaction });
+            }
+        }
+
+        match highest {
+            Some(highest) if highest >= next => next = highest + 1,
+            _ => break,
+        }
+    }
+    transactions.retain(|tx| (start..end).contains(&tx.id));
+    transactions.sort_by_key(|tx| tx.id);
+
+    Ok(transactions)
+}
+
+// fetch_archived
+async fn fetch_archived<B: CanisterBackend>(
+    backend: &B,
+    callback: &QueryArchiveFn,
+    start: u64,
+    end: u64,
+) -> Result<Vec<TransactionWithId>, Box<dyn std::error::Error>> {
+    let archive = callback.0.principal;
+    let mut transactions = Vec::new();
+    let mut next = start;
+
+    while next < end {
+        println!("Fetching blocks {}..{} from archive {}", next, end, archive);
+        let args = GetBlocksArgs { start: next, length: (end - next).min(BLOCKS_PER_CALL) };
+        let response = backend.query_bytes(archive, &callback.0.method, Encode!(&args)?).await?;
+        let blocks = match Decode!(&response, QueryArchiveResult)? {
+            QueryArchiveResult::Ok(range) => range.blocks,
+            QueryArchiveResult::Err(err) => return Err(format!("archive {archive}: {err:?}").into()),
+        };
+        if blocks.is_empty() {
+            break;
+        }
+
+        let first = next;
+        next += blocks.len() as u64;
+        for (i, block) in blocks.into_iter().enumerate() {
+            if let Some(transaction) = block.into_transaction() {
+                transactions.push(TransactionWithId { id: first + i as u64, transaction });
+            }
+        }
+    }
+
+    Ok(transactions)
+}
+
+// account_balance
+async fn account_balance<B: CanisterBackend>(backend: &B, account: &str) -> Result<u64, Box<dyn std::error::Error>> {
+    let ledger = Principal::from_text(LEDGER_CANISTER_ID)?;
+    let args = AccountBalanceArgs { account: serde_bytes::ByteBuf::from(hex::decode(account)?) };
+    let response = backend.query_bytes(ledger, "account_balance", Encode!(&args)?).await?;
+
+    Ok(Decode!(&response, Tokens)?.e8s)
+}
+
+// account_transactions_from_ledger
+// What fetch_account_transactions builds from the index, built from one scan of
+// the ledger blocks in [start, end) for all entries at once. Node provider
+// reward accounts are included as extra accounts, as with the index. Each result
+// records the scanned range, as transfers outside it are missing; the balance is
+// left out when the ledger can't be asked for one of the accounts.
+pub async fn account_transactions_from_ledger<B: CanisterBackend>(
+    backend: &B,
+    entries: &[AccountData],
+    rewards_by_principal: &HashMap<String, ProviderRewardInfo>,
+    start: u64,
+    end: u64,
+    date_filter: Option<DateRangeFilter>,
+) -> Result<Vec<AccountTransactionsJson>, Box<dyn std::error::Error>> {
+    println!("Scanning ledger blocks {}..{}", start, end);
+    let mut transactions = fetch_ledger_transactions(backend, start, end).await?;
+    if let Some(filter) = date_filter {
+        transactions.retain(|tx| filter.contains_transaction(&tx.transaction));
+    }
+
+    let mut results = Vec::new();
+    for entry in entries {
+        let mut account_tx = AccountTransactionsJson::label_only(entry, None);
+        let Some(account) = account_tx.account.clone() else { continue };
+        let reward_account = entry
+            .principal
+            .and_then(|principal| rewards_by_principal.get(&principal.to_text()))
+            .and_then(|info| info.reward_account_formatted.clone());
+        account_tx.extra_accounts.extend(reward_account.filter(|extra| *extra != account));
+
+        let owned: HashSet<&str> =
+            std::iter::once(&account).chain(&account_tx.extra_accounts).map(String::as_str).collect();
+        let mut balance = Some(0u64);
+        for owned_account in &owned {
+            match account_balance(backend, owned_account).await {
+                Ok(e8s) => balance = balance.map(|total| total.saturating_add(e8s)),
+                Err(e) => {
+                    eprintln!("Error fetching the balance of {}: {}", owned_account, e);
+                    balance = None;
+                }
+            }
+        }
+
+        account_tx.transactions = transactions
+            .iter()
+            .rev()
+            .filter_map(SimplifiedTransfer::from_transaction)
+            .filter(|tx| owned.contains(tx.from.as_str()) || owned.contains(tx.to.as_str()))
+            .collect();
+        account_tx.oldest_tx_id = account_tx.transactions.last().map(|tx| tx.id);
+        account_tx.balance_e8s = balance;
+        account_tx.scanned_blocks = Some(ScannedBlocks { start, end });
+        results.push(account_tx);
+    }
+
+    Ok(results)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{canister::MemoryBackend, Type};
+
+    const ALPHA: &str = "609d3e1e45103a82adc97d4f88c51f78dedb25701e8e51e8c4fec53448aadc29";
+    const BRAVO: &str = "220c3a33f90601896e26f76fa619fe288742df1fa75426edfaf759d39f2455a5";
+    const OTHER: &str = "d3e13d4777e22367532053190b6c6ccf57444a61337e996242b1abfb52cf92c8";
+
+    fn transfer(from: &str, to: &str, e8s: u64) -> LedgerBlock {
+        LedgerBlock {
+            parent_hash: None,
+            transaction: LedgerTransaction {
+                memo: 0,
+                icrc1_memo: None,
+                operation: Some(LedgerOperation::Transfer {
+                    from: serde_bytes::ByteBuf::from(hex::decode(from).unwrap()),
+                    to: serde_bytes::ByteBuf::from(hex::decode(to).unwrap()),
+                    amount: Tokens { e8s },
+                    fee: Tokens { e8s: 10_000 },
+                    spender: None,
+                }),
+                created_at_time: TimeStamp { timestamp_nanos: 0 },
+            },
+            timestamp: TimeStamp { timestamp_nanos: 0 },
+        }
+    }
+
+    fn backend() -> MemoryBackend {
+        let ledger = Principal::from_text(LEDGER_CANISTER_ID).unwrap();
+        let mut backend = MemoryBackend::default();
+        let response = QueryBlocksResponse {
+            chain_length: 12,
+            certificate: None,
+            blocks: vec![transfer(ALPHA, BRAVO, 500), transfer(OTHER, OTHER, 700)],
+            first_block_index: 10,
+            archived_blocks: Vec::new(),
+        };
+        backend.respond(
+            ledger,
+            "query_blocks",
+            Encode!(&GetBlocksArgs { start: 10, length: 2 }).unwrap(),
+            Encode!(&response).unwrap(),
+        );
+        // the ledger only answers the balance of ALPHA
+        let args = AccountBalanceArgs { account: serde_bytes::ByteBuf::from(hex::decode(ALPHA).unwrap()) };
+        backend.respond(ledger, "account_balance", Encode!(&args).unwrap(), Encode!(&Tokens { e8s: 900 }).unwrap());
+        backend
+    }
+
+    #[tokio::test]
+    async fn ledger_histories_record_the_scanned_range_and_skip_failed_balances() {
+        let entries = [
+            AccountData::new("Alpha", ALPHA, Type::Identified).unwrap(),
+            AccountData::new("Bravo", BRAVO, Type::Identified).unwrap(),
+        ];
+
+        let results =
+            account_transactions_from_ledger(&backend(), &entries, &HashMap::new(), 10, 12, None).await.unwrap();
+
+        assert_eq!(results.len(), 2);
+        for account_tx in &results {
+            let ids: Vec<u64> = account_tx.transactions.iter().map(|tx| tx.id).collect();
+            assert_eq!(ids, [10], "{}", account_tx.name);
+            assert_eq!(account_tx.scanned_blocks.map(|range| (range.start, range.end)), Some((10, 12)));
+        }
+        assert_eq!(results[0].balance_e8s, Some(900));
+        assert_eq!(results[1].balance_e8s, None);
+    }
+}
@@ -1,14 +1,55 @@
 pub mod addresses;
+pub mod canister;
+pub mod cli;
+pub mod clusters;
+pub mod csv_export;
+pub mod entities;
+pub mod export_filter;
+pub mod flows;
+pub mod graph;
+pub mod graph_export;
 pub mod helper;
+pub mod icpswap;
+pub mod icrc;
+pub mod icrc3;
+pub mod index_check;
+pub mod labels;
+pub mod ledger;
+pub mod neurons;
+pub mod ownership;
+pub mod rewards_flow;
+pub mod sns;
+pub mod sns_swap;
+pub mod spam;
+pub mod sqlite_export;
+pub mod trace;
 pub mod transactions;
 
-use addresses::{CEXES, DEFI, FOUNDATION, IDENTIFIED, NODE_PROVIDERS, SNSES, SNS_PARTICIPANTS, SPAMMERS, SUSPECTS};
+use addresses::{
+    CEXES, DEFI, FOUNDATION, IDENTIFIED, NODE_PROVIDERS, SNSES, SNS_ALIASES, SNS_PARTICIPANTS, SPAMMERS, SUSPECTS,
+    TAGS, TOKEN_LEDGERS,
+};
 use candid::Principal;
+use canister::{FixtureBackend, RecordingBackend};
+use clap::Parser;
+use cli::{Args, Command, IcrcArgs, RewardsToExchangesArgs, TraceArgs, VerifySnsArgs};
+use entities::ExportLevel;
+use flows::summarize_counterparties;
+use graph::Graph;
+use helper::{block_range, parse_icrc1_account, principal_to_account_id};
 use ic_agent::Agent;
+use icpswap::IcpSwapSnapshot;
+use labels::LabelIndex;
+use ledger::TransactionSource;
+use rewards_flow::rewards_to_exchanges;
 use serde::{Deserialize, Serialize};
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use thiserror::Error as ThisError;
-use transactions::fetch_account_transactions;
+use trace::{resolve_account, trace, TraceOptions};
+use transactions::{
+    fetch_account_transactions, fetch_nodes_rewards, process_rewards_data, reward_records, AccountTransactionsJson,
+    DateRangeFilter,
+};
 
 const IC_URL: &str = "https://ic0.app";
 ///
@@ -28,23 +69,67 @@ pub enum Error {
 /// AccountData
 ///
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AccountData {
     name: String,
     principal: Option<Principal>,
+    subaccount: Option<[u8; 32]>,
     account: Option<String>,
     ty: Type,
+    subcategory: Option<String>,
+    tags: Vec<String>,
+    genesis_icp: Option<u64>,
 }
 
 impl AccountData {
-    pub fn new(name: &str, address: &str, ty: Type) -> Self {
-        let (principal, account) = if address.contains("-") {
-            (Some(Principal::from_text(address).unwrap()), None)
+    // new
+    // address is a hex account id, a principal, or an ICRC-1 textual account
+    pub fn new(name: &str, address: &str, ty: Type) -> Result<Self, String> {
+        let (principal, subaccount, account) = if address.contains('-') {
+            let (principal, subaccount) = parse_icrc1_account(address).map_err(|e| format!("{name}: {e}"))?;
+            (Some(principal), subaccount, None)
         } else {
-            (None, Some(address.to_string()))
+            (None, None, Some(address.to_string()))
         };
 
-        Self { name: name.to_string(), principal, account, ty }
+        Ok(Self {
+            name: name.to_string(),
+            principal,
+            subaccount,
+            account,
+            ty,
+            subcategory: None,
+            tags: Vec::new(),
+            genesis_icp: None,
+        })
+    }
+
+    // with_subcategory
+    pub fn with_subcategory(mut self, subcategory: &str) -> Self {
+        self.subcategory = Some(subcategory.to_string());
+        self
+    }
+
+    // with_genesis_icp
+    pub fn with_genesis_icp(mut self, icp: u64) -> Self {
+        self.genesis_icp = Some(icp);
+        self
+    }
+
+    // has_tag
+    // matches a tag or the subcategory, ignoring case
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.subcategory.iter().chain(&self.tags).any(|t| t.eq_ignore_ascii_case(tag))
+    }
+
+    // account_id
+    // hex account id, derived from the principal and subaccount when there is one
+    pub fn account_id(&self) -> Option<String> {
+        match (&self.principal, &self.account) {
+            (Some(principal), _) => Some(hex::encode(principal_to_account_id(principal, self.subaccount))),
+            (None, Some(account)) => Some(account.clone()),
+            (None, None) => None,
+        }
     }
 }
 
@@ -52,12 +137,13 @@ impl AccountData {
 /// AccountType
 ///
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, clap::ValueEnum)]
 pub enum Type {
     Cex,
     Defi,
     Foundation,
     Identified,
+    Neuron,
     NodeProvider,
     Spammer,
     Sns,
@@ -71,16 +157,212 @@ pub enum Type {
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let args = Args::parse();
+    let date_filter = args.date_filter();
+    if let Some(filter) = &date_filter {
+        if let (Some(since), Some(until)) = (filter.start_timestamp_seconds, filter.end_timestamp_seconds) {
+            if since > until {
+                return Err("--since must not be later than --until".into());
+            }
+        }
+    }
+
     let agent = Agent::builder().with_url(IC_URL).build()?;
 
+    let entries = get_entries();
+    let sns_participations = sns::participations(&entries, SNSES, SNS_ALIASES)?;
+
+    // runs offline when answering from fixtures
+    if let Some(Command::VerifySns(verify_args)) = &args.command {
+        return run_verify_sns(verify_args, &agent, &sns_participations).await;
+    }
+
     // Initialize the agent (fetch root key in development)
     agent.fetch_root_key().await?;
 
+    let icpswap = IcpSwapSnapshot::load()?;
+    let icpswap_entries = icpswap.entries();
+    let known_accounts: HashSet<String> = entries.iter().filter_map(AccountData::account_id).collect();
+    let mut labels = LabelIndex::from_entries(&entries);
+    labels.extend(&icpswap_entries);
+
+    match &args.command {
+        Some(Command::Trace(trace_args)) => {
+            return run_trace(trace_args, &agent, &entries, labels, date_filter).await;
+        }
+        Some(Command::RewardsToExchanges(flow_args)) => {
+            return run_rewards_to_exchanges(flow_args, &agent, &entries, labels, date_filter).await;
+        }
+        Some(Command::Icrc(icrc_args)) => {
+            return run_icrc(icrc_args, &agent, &entries, &icpswap, date_filter).await;
+        }
+        Some(Command::VerifySns(_)) | None => {}
+    }
+
     let mut results = Vec::new();
-    for entry in get_entries() {
-        match fetch_account_transactions(entry, &agent).await {
+    let mut from_ledger = Vec::new();
+    let selected =
+        entries.into_iter().filter(|entry| args.tag.is_empty() || args.tag.iter().any(|tag| entry.has_tag(tag)));
+    for entry in selected {
+        if args.source == TransactionSource::Ledger {
+            from_ledger.push(entry);
+            continue;
+        }
+        match fetch_account_transactions(entry.clone(), &agent, date_filter).await {
             Ok(account_tx) => results.push(account_tx),
-            Err(e) => eprintln!("Error fetching account transactions: {}", e),
+            Err(e) => {
+                eprintln!("Error fetching account transactions: {}", e);
+                if args.source == TransactionSource::Fallback {
+                    from_ledger.push(entry);
+                }
+            }
+        }
+    }
+    if !from_ledger.is_empty() {
+        match ledger::chain_length(&agent).await {
+            Ok(chain_length) => {
+                let (start, end) = block_range(args.start_block, args.block_count, chain_length);
+                let rewards_by_principal = match fetch_nodes_rewards(&agent, None).await {
+                    Ok(rewards) => process_rewards_data(rewards),
+                    Err(e) => {
+                        eprintln!("Error fetching node provider rewards, scanning without reward accounts: {}", e);
+                        HashMap::new()
+                    }
+                };
+                let scan = ledger::account_transactions_from_ledger(
+                    &agent,
+                    &from_ledger,
+                    &rewards_by_principal,
+                    start,
+                    end,
+                    date_filter,
+                );
+                match scan.await {
+                    Ok(account_txs) => results.extend(account_txs),
+                    Err(e) => eprintln!("Error scanning ledger blocks {}..{}: {}", start, end, e),
+                }
+            }
+            Err(e) => {
+                eprintln!("Error fetching the ledger chain length, skipping {} accounts: {}", from_ledger.len(), e)
+            }
+        }
+    }
+    if !args.skip_sns_accounts {
+        for account_tx in results.iter_mut().filter(|account_tx| account_tx.ty == Type::Sns) {
+            if let Err(e) = sns_swap::fetch_sns_accounts(account_tx, &agent, date_filter).await {
+                eprintln!("Error fetching SNS treasury and swap accounts: {}", e);
+            }
+        }
+    }
+    if let Some(path) = &args.verify_index {
+        let report = index_check::check_index(&agent, &results, args.verify_sample).await;
+        std::fs::write(path, serde_json::to_string_pretty(&report)?)?;
+        println!(
+            "Saved {} mismatches in {} of {} transfers checked against the ledger to {}",
+            report.mismatched,
+            report.checked,
+            report.transfer_count,
+            path.display()
+        );
+    }
+    results.extend(get_icpswap_labels(&icpswap, icpswap_entries, &results, &known_accounts));
+    labels.extend_from_results(&results);
+    labels.extend_neuron_stakes(neurons::neuron_stake_accounts(&results));
+    for account_tx in &mut results {
+        labels.annotate_account(account_tx);
+    }
+
+    // detection needs the dust the export filter may drop
+    let spam_candidates = spam::detect_spammers(&results, &labels, &args.spam_options());
+    if let Some(path) = &args.spam_candidates {
+        std::fs::write(path, serde_json::to_string_pretty(&spam_candidates)?)?;
+        println!("Saved {} spam candidates to {}", spam_candidates.len(), path.display());
+    }
+
+    let rewards = if args.sqlite.is_some() || args.ownership.is_some() {
+        reward_records(&fetch_nodes_rewards(&agent, date_filter).await?)
+    } else {
+        Vec::new()
+    };
+
+    // analyses look at every transfer of every address, before filtering and roll-up
+    for account_tx in &mut results {
+        account_tx.counterparties = summarize_counterparties(account_tx);
+    }
+    if let Some(path) = &args.clusters {
+        let report = clusters::find_clusters(&results);
+        std::fs::write(path, serde_json::to_string_pretty(&report)?)?;
+        println!(
+            "Saved {} candidate accounts in {} clusters to {}",
+            report.candidate_count,
+            report.clusters.len(),
+            path.display()
+        );
+    }
+
+    if let Some(path) = &args.ownership {
+        let pairs = ownership::common_ownership(&results, &rewards);
+        std::fs::write(path, serde_json::to_string_pretty(&pairs)?)?;
+        println!("Saved {} node provider pairs with shared ownership evidence to {}", pairs.len(), path.display());
+    }
+
+    for account_tx in &mut results {
+        export_filter::filter_transfers(account_tx, args.min_amount_e8s, args.spam_transfers);
+    }
+
+    if args.level == ExportLevel::Entity {
+        results = entities::roll_up(results);
+        labels.roll_up();
+        for account_tx in &mut results {
+            labels.annotate_account(account_tx);
+        }
+    }
+    // again for what is exported
+    for account_tx in &mut results {
+        account_tx.counterparties = summarize_counterparties(account_tx);
+    }
+
+    let graph = if args.exclude_spam {
+        Graph::build_excluding(&results, &spam::spam_accounts(&labels, &spam_candidates))
+    } else {
+        Graph::build(&results)
+    };
+    let json_string = serde_json::to_string_pretty(&graph)?;
+    std::fs::write("./../frontend/public/graph.json", json_string)?;
+    println!("Saved graph with {} nodes and {} links to graph.json", graph.nodes.len(), graph.links.len());
+
+    if let Some(path) = &args.graphml {
+        std::fs::write(path, graph_export::to_graphml(&graph))?;
+        println!("Saved GraphML to {}", path.display());
+    }
+    if let Some(path) = &args.gexf {
+        std::fs::write(path, graph_export::to_gexf(&graph))?;
+        println!("Saved GEXF to {}", path.display());
+    }
+    if let Some(path) = &args.dot {
+        std::fs::write(path, graph_export::to_dot(&graph))?;
+        println!("Saved DOT to {}", path.display());
+    }
+
+    if let Some(dir) = &args.csv {
+        csv_export::write_csv_dir(dir, &results)?;
+        println!("Saved transactions.csv and entities.csv to {}", dir.display());
+    }
+
+    if let Some(path) = &args.sqlite {
+        sqlite_export::write_sqlite(path, &results, &rewards, &sns_participations, &labels)?;
+        println!("Saved SQLite database to {}", path.display());
+    }
+
+    if let Some(path) = &args.sns_participation {
+        let report = sns::report(sns_participations);
+        std::fs::write(path, serde_json::to_string_pretty(&report)?)?;
+        println!("Saved participation in {} SNS swaps to {}", report.totals.len(), path.display());
+    }
+
+    for account_tx in &mut results {
+        if args.max_transfers.is_some_and(|max| account_tx.transactions.len() > max) {
+            account_tx.transactions.clear();
         }
     }
 
@@ -91,20 +373,240 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
+// run_trace
+async fn run_trace(
+    trace_args: &TraceArgs,
+    agent: &Agent,
+    entries: &[AccountData],
+    mut labels: LabelIndex,
+    date_filter: Option<DateRangeFilter>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    // reward accounts are labelled from the full reward history, whatever the window
+    let rewards = reward_records(&fetch_nodes_rewards(agent, None).await?);
+    labels.extend_from_rewards(entries, &rewards);
+
+    let source = resolve_account(&trace_args.source)?;
+    let options = TraceOptions {
+        max_hops: trace_args.max_hops,
+        max_fan_out: trace_args.max_fan_out,
+        min_amount_e8s: trace_args.min_amount_e8s,
+        stop_at: trace_args.stop_at.clone(),
+        date_filter,
+    };
+    let report = trace(agent, &source, &labels, &options).await?;
+
+    for path in &report.paths {
+        let amount_e8s = path.hops.last().map_or(0, |hop| hop.amount_e8s);
+        println!(
+            "{} ({:?}) in {} hop(s), {:.2} ICP on the last hop",
+            path.label.name,
+            path.label.ty,
+            path.hops.len(),
+            amount_e8s as f64 / 100_000_000.0
+        );
+    }
+
+    std::fs::write(&trace_args.output, serde_json::to_string_pretty(&report)?)?;
+    println!(
+        "Saved {} paths from {} visited accounts to {}",
+        report.paths.len(),
+        report.accounts_visited,
+        trace_args.output.display()
+    );
+
+    Ok(())
+}
+
+// run_rewards_to_exchanges
+async fn run_rewards_to_exchanges(
+    flow_args: &RewardsToExchangesArgs,
+    agent: &Agent,
+    entries: &[AccountData],
+    mut labels: LabelIndex,
+    date_filter: Option<DateRangeFilter>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let rewards = reward_records(&fetch_nodes_rewards(agent, date_filter).await?);
+    labels.extend_from_rewards(entries, &rewards);
+
+    let options = TraceOptions {
+        max_hops: flow_args.max_hops,
+        max_fan_out: flow_args.max_fan_out,
+        min_amount_e8s: flow_args.min_amount_e8s,
+        stop_at: Vec::new(),
+        date_filter,
+    };
+    let report = rewards_to_exchanges(agent, entries, &rewards, &labels, &options).await?;
+
+    for provider in report.providers.iter().filter(|p| p.to_exchanges_e8s > 0) {
+        println!(
+            "{}: {:.2} of {:.2} ICP minted reached exchanges",
+            provider.name,
+            provider.to_exchanges_e8s as f64 / 100_000_000.0,
+            provider.minted_e8s as f64 / 100_000_000.0
+        );
+    }
+
+    std::fs::write(&flow_args.output, serde_json::to_string_pretty(&report)?)?;
+    println!(
+        "Saved {:.2} of {:.2} ICP minted reaching exchanges to {}",
+        report.to_exchanges_e8s as f64 / 100_000_000.0,
+        report.minted_e8s as f64 / 100_000_000.0,
+        flow_args.output.display()
+    );
+
+    Ok(())
+}
+
+// run_verify_sns
+async fn run_verify_sns(
+    verify_args: &VerifySnsArgs,
+    agent: &Agent,
+    participations: &[sns::SnsParticipation],
+) -> Result<(), Box<dyn std::error::Error>> {
+    let tolerance = verify_args.tolerance_percent;
+    let report = if let Some(dir) = &verify_args.fixtures {
+        let backend = FixtureBackend { dir: dir.clone() };
+        sns_swap::verify_participation(&backend, participations, tolerance).await?
+    } else {
+        agent.fetch_root_key().await?;
+        match &verify_args.record {
+            Some(dir) => {
+                let backend = RecordingBackend { inner: agent, dir: dir.clone() };
+                sns_swap::verify_participation(&backend, participations, tolerance).await?
+            }
+            None => sns_swap::verify_participation(agent, participations, tolerance).await?,
+        }
+    };
+
+    for check in report.checks.iter().filter(|check| check.status != sns_swap::VerificationStatus::Match) {
+        println!(
+            "{} in {}: claimed {:.2} ICP, {:?} on chain",
+            check.participant,
+            check.sns,
+            check.claimed_e8s as f64 / 100_000_000.0,
+            check.onchain_e8s.map(|e8s| e8s as f64 / 100_000_000.0)
+        );
+    }
+
+    std::fs::write(&verify_args.output, serde_json::to_string_pretty(&report)?)?;
+    println!(
+        "Saved {} matched, {} mismatched and {} missing participations to {}",
+        report.matched,
+        report.mismatched,
+        report.not_found,
+        verify_args.output.display()
+    );
+
+    Ok(())
+}
+
+// run_icrc
+async fn run_icrc(
+    icrc_args: &IcrcArgs,
+    agent: &Agent,
+    entries: &[AccountData],
+    icpswap: &IcpSwapSnapshot,
+    date_filter: Option<DateRangeFilter>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut ledgers = icrc_args.ledger.clone();
+    if ledgers.is_empty() {
+        for (_, ledger, index) in TOKEN_LEDGERS {
+            ledgers.push((Principal::from_text(ledger)?, Some(Principal::from_text(index)?)));
+        }
+    }
+    if icrc_args.sns {
+        for (name, root) in SNSES {
+            match sns_swap::list_sns_canisters(agent, Principal::from_text(root)?).await {
+                Ok(sns_swap::SnsCanisters { ledger: Some(ledger), index, .. }) => ledgers.push((ledger, index)),
+                Ok(_) => eprintln!("{name} lists no ledger"),
+                Err(e) => eprintln!("Error listing the canisters of {}: {}", name, e),
+            }
+        }
+    }
+
+    let mut histories = Vec::new();
+    for (ledger, index) in ledgers {
+        let token = match icrc::token_ledger(agent, ledger, index).await {
+            Ok(token) => token,
+            Err(e) => {
+                eprintln!("Error fetching token metadata for ledger {}: {}", ledger, e);
+                continue;
+            }
+        };
+        let history = if icrc_args.icrc3 || index.is_none() {
+            let log_length = match icrc3::log_length(agent, ledger).await {
+                Ok(log_length) => log_length,
+                Err(e) => {
+                    eprintln!("Error fetching the log length of ledger {}: {}", ledger, e);
+                    continue;
+                }
+            };
+            let (start, end) = block_range(icrc_args.start_block, icrc_args.block_count, log_length);
+            icrc3::fetch_token_history_from_blocks(agent, token, entries, start, end, date_filter).await
+        } else {
+            icrc::fetch_token_history(agent, token, entries, date_filter).await
+        };
+        let mut history = match history {
+            Ok(history) => history,
+            Err(e) => {
+                eprintln!("Error fetching the history of ledger {}: {}", ledger, e);
+                continue;
+            }
+        };
+        history.apply_prices(icpswap);
+        println!("{}: {} labelled accounts", history.token.symbol, history.accounts.len());
+        histories.push(history);
+    }
+
+    std::fs::write(&icrc_args.output, serde_json::to_string_pretty(&histories)?)?;
+    println!("Saved {} token histories to {}", histories.len(), icrc_args.output.display());
+
+    Ok(())
+}
+
+// get_icpswap_labels
+// ICPSwap pools and ledgers that our accounts transacted with, exported as DeFi labels
+fn get_icpswap_labels(
+    icpswap: &IcpSwapSnapshot,
+    icpswap_entries: Vec<AccountData>,
+    results: &[AccountTransactionsJson],
+    known_accounts: &HashSet<String>,
+) -> Vec<AccountTransactionsJson> {
+    let counterparties: HashSet<&str> =
+        results.iter().flat_map(|r| &r.transactions).flat_map(|tx| [tx.from.as_str(), tx.to.as_str()]).collect();
+
+    icpswap_entries
+        .into_iter()
+        .filter_map(|entry| {
+            let account = entry.account_id()?;
+            if known_accounts.contains(&account) || !counterparties.contains(account.as_str()) {
+                return None;
+            }
+            let valuation = entry.principal.and_then(|p| icpswap.valuation(&p.to_text()));
+
+            Some(AccountTransactionsJson::label_only(&entry, valuation))
+        })
+        .collect()
+}
+
 // get_entries
 fn get_entries() -> Vec<AccountData> {
     let mut entries = Vec::new();
 
     // named
-    entries.extend(CEXES.iter().map(|(name, addr)| AccountData::new(name, addr, Type::Cex)));
-    entries.extend(DEFI.iter().map(|(name, addr)| AccountData::new(name, addr, Type::Defi)));
-    entries.extend(IDENTIFIED.iter().map(|(name, addr)| AccountData::new(name, addr, Type::Identified)));
-    entries.extend(NODE_PROVIDERS.iter().map(|(name, addr)| AccountData::new(name, addr, Type::NodeProvider)));
-    entries.extend(SNSES.iter().map(|(name, addr)| AccountData::new(name, addr, Type::Sns)));
-    entries.extend(SNS_PARTICIPANTS.iter().map(|(name, addr)| AccountData::new(name, addr, Type::SnsParticipant)));
-    entries.extend(SUSPECTS.iter().map(|(name, addr)| AccountData::new(name, addr, Type::Suspect)));
-    entries.extend(FOUNDATION.iter().map(|(name, addr)| AccountData::new(name, addr, Type::Foundation)));
-    entries.extend(SPAMMERS.iter().map(|addr| AccountData::new(&addr[..5], addr, Type::Spammer)));
+    entries.extend(CEXES.iter().filter_map(|(name, addr)| entry(name, addr, Type::Cex)));
+    entries.extend(DEFI.iter().filter_map(|(name, addr)| entry(name, addr, Type::Defi)));
+    entries.extend(IDENTIFIED.iter().filter_map(|(name, addr)| entry(name, addr, Type::Identified)));
+    entries.extend(NODE_PROVIDERS.iter().filter_map(|(name, addr)| entry(name, addr, Type::NodeProvider)));
+    entries.extend(SNSES.iter().filter_map(|(name, addr)| entry(name, addr, Type::Sns)));
+    entries.extend(grouped_entries(SNS_PARTICIPANTS, Type::SnsParticipant));
+    entries.extend(grouped_entries(SUSPECTS, Type::Suspect));
+    entries.extend(FOUNDATION.iter().flat_map(|(subcategory, list)| {
+        list.iter().filter_map(move |(name, icp, addr)| {
+            Some(entry(name, addr, Type::Foundation)?.with_subcategory(subcategory).with_genesis_icp(*icp))
+        })
+    }));
+    entries.extend(SPAMMERS.iter().filter_map(|addr| entry(&addr[..5], addr, Type::Spammer)));
 
     // no name
 
@@ -120,12 +622,36 @@ fn get_entries() -> Vec<AccountData> {
         }
 
         if let Some(pid) = &entry.principal {
-            if !seen_principals.insert(pid) {
+            if !seen_principals.insert((pid, entry.subaccount)) {
                 panic!("duplicate principal found: {pid}");
             }
         }
     }
     println!(" ok");
 
+    for (address, tags) in TAGS {
+        let account_id = AccountData::new(address, address, Type::Identified).ok().and_then(|e| e.account_id());
+        match entries.iter_mut().find(|entry| entry.account_id() == account_id) {
+            Some(entry) => entry.tags.extend(tags.iter().map(|tag| tag.to_string())),
+            None => panic!("tagged address not in the address book: {address}"),
+        }
+    }
+
     entries
 }
+
+// entry
+// a malformed address book line is reported and skipped
+fn entry(name: &str, address: &str, ty: Type) -> Option<AccountData> {
+    AccountData::new(name, address, ty).map_err(|e| eprintln!("Skipping address book entry {e}")).ok()
+}
+
+// grouped_entries
+fn grouped_entries(groups: &[(&str, &[(&str, &str)])], ty: Type) -> Vec<AccountData> {
+    groups
+        .iter()
+        .flat_map(|(subcategory, list)| {
+            list.iter().filter_map(move |(name, addr)| Some(entry(name, addr, ty)?.with_subcategory(subcategory)))
+        })
+        .collect()
+}
@@ -0,0 +1,130 @@
+use crate::{
+    helper::principal_to_account_id,
+    transactions::{AccountTransactionsJson, SimplifiedTransfer, GOVERNANCE_CANISTER_ID},
+};
+use candid::Principal;
+use sha2::{Digest, Sha256};
+use std::collections::HashSet;
+
+pub const NEURON_STAKE_LABEL: &str = "Neuron stake";
+
+// staking_subaccount
+// the governance subaccount a controller stakes into, sha256(0x0c "neuron-stake" principal memo)
+pub fn staking_subaccount(controller: &Principal, memo: u64) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update([0x0c]);
+    hasher.update(b"neuron-stake");
+    hasher.update(controller.as_slice());
+    hasher.update(memo.to_be_bytes());
+
+    hasher.finalize().into()
+}
+
+// neuron_stake_accounts
+// Staking sends ICP from the controller with the neuron's nonce as memo, so a
+// transfer from an account of a known principal is a stake when recomputing the
+// staking subaccount from that principal and the memo gives the recipient.
+// Later transfers to the same neuron account are top ups and get the label too.
+pub fn neuron_stake_accounts(results: &[AccountTransactionsJson]) -> HashSet<String> {
+    let governance = Principal::from_text(GOVERNANCE_CANISTER_ID).expect("valid governance canister id");
+    let mut accounts = HashSet::new();
+
+    for account_tx in results {
+        let Some(principal) = &account_tx.principal else { continue };
+        let controller = match Principal::from_text(principal) {
+            Ok(controller) => controller,
+            Err(e) => {
+                eprintln!("Skipping neuron stakes of {}: {}", account_tx.name, e);
+                continue;
+            }
+        };
+
+        for tx in account_tx.transactions.iter().filter(|tx| account_tx.owns(&tx.from)) {
+            let is_stake = nonces(tx).any(|nonce| {
+                hex::encode(principal_to_account_id(&governance, Some(staking_subaccount(&controller, nonce)))) == tx.to
+            });
+            if is_stake {
+                accounts.insert(tx.to.clone());
+            }
+        }
+    }
+
+    accounts
+}
+
+// nonces
+// ICRC-1 stakes carry the nonce as an 8 byte big endian icrc1_memo and leave
+// the legacy memo at 0, so both are candidates
+fn nonces(tx: &SimplifiedTransfer) -> impl Iterator<Item = u64> {
+    let icrc1 = tx
+        .icrc1_memo
+        .as_deref()
+        .and_then(|memo| hex::decode(memo).ok())
+        .and_then(|bytes| <[u8; 8]>::try_from(bytes.as_slice()).ok())
+        .map(u64::from_be_bytes);
+
+    std::iter::once(tx.memo).chain(icrc1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{AccountData, Type};
+
+    const CONTROLLER: &str = "vgrm6-ur624-kl4qd-3sukh-wssor-ztoox-ecgbu-ebvn5-f4hgu-quqyk-pae";
+    // sha256(0x0c "neuron-stake" CONTROLLER 12345u64 BE) and the governance account under it
+    const SUBACCOUNT: &str = "62c68dd614619f3b83bbf2e512158bc88a9d2ed0f5042238665818fc790bbac4";
+    const NEURON_ACCOUNT: &str = "3452811b67e48ea3bb803a768f3d075aa5567a865fec8c5031d821a87b4dfe47";
+
+    fn transfer(id: u64, from: &str, to: &str, memo: u64, icrc1_memo: Option<u64>) -> SimplifiedTransfer {
+        SimplifiedTransfer {
+            memo,
+            icrc1_memo: icrc1_memo.map(|nonce| hex::encode(nonce.to_be_bytes())),
+            ..SimplifiedTransfer::transfer(id, from, to, 100_000_000)
+        }
+    }
+
+    fn controller_account(transactions: Vec<SimplifiedTransfer>) -> AccountTransactionsJson {
+        let entry = AccountData::new("Controller", CONTROLLER, Type::Identified).unwrap();
+        let mut account_tx = AccountTransactionsJson::label_only(&entry, None);
+        account_tx.transactions = transactions;
+        account_tx
+    }
+
+    #[test]
+    fn staking_subaccount_matches_the_governance_derivation() {
+        let controller = Principal::from_text(CONTROLLER).unwrap();
+        let governance = Principal::from_text(GOVERNANCE_CANISTER_ID).unwrap();
+        let subaccount = staking_subaccount(&controller, 12345);
+
+        assert_eq!(hex::encode(subaccount), SUBACCOUNT);
+        assert_eq!(hex::encode(principal_to_account_id(&governance, Some(subaccount))), NEURON_ACCOUNT);
+    }
+
+    #[test]
+    fn stakes_are_found_by_legacy_or_icrc1_memo() {
+        let own = controller_account(Vec::new()).account.unwrap();
+        let results = [controller_account(vec![
+            transfer(1, &own, NEURON_ACCOUNT, 12345, None),
+            transfer(2, &own, NEURON_ACCOUNT, 0, Some(12345)),
+            transfer(3, &own, "ff", 12345, None),
+            // received, not staked
+            transfer(4, "ff", NEURON_ACCOUNT, 12345, None),
+        ])];
+
+        assert_eq!(neuron_stake_accounts(&results), HashSet::from([NEURON_ACCOUNT.to_string()]));
+
+        let icrc1_only = [controller_account(vec![transfer(2, &own, NEURON_ACCOUNT, 0, Some(12345))])];
+        assert_eq!(neuron_stake_accounts(&icrc1_only).len(), 1);
+        let other_nonce = [controller_account(vec![transfer(2, &own, NEURON_ACCOUNT, 0, Some(54321))])];
+        assert!(neuron_stake_accounts(&other_nonce).is_empty());
+    }
+
+    #[test]
+    fn malformed_principals_are_skipped() {
+        let mut account_tx = controller_account(Vec::new());
+        account_tx.principal = Some("not a principal".to_string());
+
+        assert!(neuron_stake_accounts(&[account_tx]).is_empty());
+    }
+}
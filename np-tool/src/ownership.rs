@@ -0,0 +1,272 @@
+use crate::{
+    transactions::{AccountTransactionsJson, RewardRecord},
+    Type,
+};
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+
+// two first outflows after the same mint within this many seconds count as synchronised
+pub const SYNC_WINDOW_SECONDS: u64 = 60 * 60;
+
+const SHARED_REWARD_ACCOUNT_WEIGHT: f64 = 10.0;
+const DIRECT_TRANSFER_WEIGHT: f64 = 5.0;
+const SYNCHRONISED_OUTFLOW_WEIGHT: f64 = 2.0;
+const SHARED_COUNTERPARTY_WEIGHT: f64 = 1.0;
+
+///
+/// SynchronisedOutflow
+///
+/// Both providers moved funds out shortly after the same reward mint.
+///
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SynchronisedOutflow {
+    pub mint_timestamp_seconds: u64,
+    pub first_seconds_after_mint: u64,
+    pub second_seconds_after_mint: u64,
+}
+
+///
+/// OwnershipEvidence
+///
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct OwnershipEvidence {
+    pub shared_reward_accounts: Vec<String>,
+    pub shared_counterparties: Vec<String>,
+    pub direct_transfer_count: u64,
+    pub direct_volume_e8s: u64,
+    pub synchronised_outflows: Vec<SynchronisedOutflow>,
+}
+
+///
+/// ProviderPair
+///
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ProviderPair {
+    pub first: String,
+    pub second: String,
+    pub score: f64,
+    pub evidence: OwnershipEvidence,
+}
+
+///
+/// Provider
+///
+/// What one node provider looks like to the heuristics.
+///
+
+#[derive(Debug)]
+struct Provider<'a> {
+    account_tx: &'a AccountTransactionsJson,
+    reward_accounts: BTreeSet<&'a str>,
+    counterparties: BTreeSet<&'a str>,
+    // mint timestamp -> seconds until the first outgoing transfer after it
+    outflow_delays: BTreeMap<u64, u64>,
+}
+
+// common_ownership
+// Scores every pair of node providers on shared reward accounts, transfers
+// between them, synchronised outflows after mints, and shared unlabelled
+// counterparties, in that order of weight. Only pairs with some evidence are
+// returned, highest score first. Expects counterparties to be summarized.
+pub fn common_ownership(results: &[AccountTransactionsJson], rewards: &[RewardRecord]) -> Vec<ProviderPair> {
+    let mut mints: Vec<u64> = rewards.iter().map(|r| r.timestamp_seconds).collect();
+    mints.sort_unstable();
+    mints.dedup();
+
+    let providers: Vec<Provider> = results
+        .iter()
+        .filter(|account_tx| account_tx.ty == Type::NodeProvider && account_tx.account.is_some())
+        .map(|account_tx| Provider {
+            account_tx,
+            reward_accounts: rewards
+                .iter()
+                .filter(|r| r.node_provider.is_some() && r.node_provider == account_tx.principal)
+                .filter_map(|r| r.reward_account.as_deref())
+                .collect(),
+            counterparties: account_tx
+                .counterparties
+                .iter()
+                .filter(|flow| flow.label.is_none())
+                .map(|flow| flow.counterparty.as_str())
+                .collect(),
+            outflow_delays: outflow_delays(account_tx, &mints),
+        })
+        .collect();
+
+    let mut pairs = Vec::new();
+    for (i, first) in providers.iter().enumerate() {
+        for second in &providers[i + 1..] {
+            let evidence = evidence(first, second);
+            let score = score(&evidence);
+            if score > 0.0 {
+                pairs.push(ProviderPair {
+                    first: first.account_tx.name.clone(),
+                    second: second.account_tx.name.clone(),
+                    score,
+                    evidence,
+                });
+            }
+        }
+    }
+    pairs.sort_by(|a, b| b.score.total_cmp(&a.score));
+
+    pairs
+}
+
+fn evidence(first: &Provider, second: &Provider) -> OwnershipEvidence {
+    let mut evidence = OwnershipEvidence {
+        shared_reward_accounts: first
+            .reward_accounts
+            .intersection(&second.reward_accounts)
+            .map(|a| a.to_string())
+            .collect(),
+        shared_counterparties: first
+            .counterparties
+            .intersection(&second.counterparties)
+            .map(|a| a.to_string())
+            .collect(),
+        ..Default::default()
+    };
+
+    // the same transfer appears in both lists, so only look at one side
+    for tx in &first.account_tx.transactions {
+        let between = (first.account_tx.owns(&tx.from) && second.account_tx.owns(&tx.to))
+            || (second.account_tx.owns(&tx.from) && first.account_tx.owns(&tx.to));
+        if between {
+            evidence.direct_transfer_count += 1;
+            evidence.direct_volume_e8s = evidence.direct_volume_e8s.saturating_add(tx.amount_e8s);
+        }
+    }
+
+    for (mint, first_delay) in &first.outflow_delays {
+        if let Some(second_delay) = second.outflow_delays.get(mint) {
+            if first_delay.abs_diff(*second_delay) <= SYNC_WINDOW_SECONDS {
+                evidence.synchronised_outflows.push(SynchronisedOutflow {
+                    mint_timestamp_seconds: *mint,
+                    first_seconds_after_mint: *first_delay,
+                    second_seconds_after_mint: *second_delay,
+                });
+            }
+        }
+    }
+
+    evidence
+}
+
+fn score(evidence: &OwnershipEvidence) -> f64 {
+    let direct = if evidence.direct_transfer_count > 0 { DIRECT_TRANSFER_WEIGHT } else { 0.0 };
+
+    evidence.shared_reward_accounts.len() as f64 * SHARED_REWARD_ACCOUNT_WEIGHT
+        + direct
+        + evidence.synchronised_outflows.len() as f64 * SYNCHRONISED_OUTFLOW_WEIGHT
+        + evidence.shared_counterparties.len() as f64 * SHARED_COUNTERPARTY_WEIGHT
+}
+
+// outflow_delays
+// for each mint, the delay until the account's first outgoing transfer before the next mint
+fn outflow_delays(account_tx: &AccountTransactionsJson, mints: &[u64]) -> BTreeMap<u64, u64> {
+    let mut delays = BTreeMap::new();
+
+    for tx in account_tx.transactions.iter().filter(|tx| account_tx.owns(&tx.from) && !account_tx.owns(&tx.to)) {
+        let Some(sent_at) = tx.timestamp_nanos.map(|ts| ts / 1_000_000_000) else { continue };
+        let Some(mint) = mints.iter().rev().find(|mint| **mint <= sent_at) else { continue };
+
+        let delay = delays.entry(*mint).or_insert(u64::MAX);
+        *delay = (*delay).min(sent_at - mint);
+    }
+
+    delays
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{transactions::SimplifiedTransfer, AccountData};
+
+    const PRINCIPALS: [&str; 3] = [
+        "vgrm6-ur624-kl4qd-3sukh-wssor-ztoox-ecgbu-ebvn5-f4hgu-quqyk-pae",
+        "eipr5-izbom-neyqh-s3ec2-52eww-cyfpg-qfomg-3dpwj-4pffh-34xcu-7qe",
+        "rrkah-fqaaa-aaaaa-aaaaq-cai",
+    ];
+
+    fn provider(name: &str, principal: &str) -> AccountTransactionsJson {
+        AccountTransactionsJson::label_only(&AccountData::new(name, principal, Type::NodeProvider).unwrap(), None)
+    }
+
+    fn reward(timestamp_seconds: u64, node_provider: Option<&str>, reward_account: Option<&str>) -> RewardRecord {
+        RewardRecord {
+            timestamp_seconds,
+            node_provider: node_provider.map(String::from),
+            amount_e8s: 100,
+            reward_account: reward_account.map(String::from),
+            xdr_permyriad_per_icp: None,
+        }
+    }
+
+    fn sent(id: u64, from: &AccountTransactionsJson, to: &str, seconds: u64) -> SimplifiedTransfer {
+        let from = from.account.as_deref().unwrap();
+        SimplifiedTransfer {
+            timestamp_nanos: Some(seconds * 1_000_000_000),
+            ..SimplifiedTransfer::transfer(id, from, to, 100)
+        }
+    }
+
+    fn names(pairs: &[ProviderPair]) -> Vec<(&str, &str)> {
+        pairs.iter().map(|pair| (pair.first.as_str(), pair.second.as_str())).collect()
+    }
+
+    #[test]
+    fn shared_reward_accounts_pair_providers_and_pairs_without_evidence_are_left_out() {
+        let results = PRINCIPALS.map(|principal| provider(&principal[..5], principal));
+        let rewards = [reward(1, Some(PRINCIPALS[0]), Some("rr")), reward(1, Some(PRINCIPALS[1]), Some("rr"))];
+
+        let pairs = common_ownership(&results, &rewards);
+        assert_eq!(names(&pairs), [("vgrm6", "eipr5")]);
+        assert_eq!(pairs[0].evidence.shared_reward_accounts, ["rr"]);
+        assert_eq!(pairs[0].score, SHARED_REWARD_ACCOUNT_WEIGHT);
+    }
+
+    #[test]
+    fn direct_transfers_listed_by_both_providers_count_once() {
+        let [mut first, mut second] =
+            [PRINCIPALS[0], PRINCIPALS[1]].map(|principal| provider(&principal[..5], principal));
+        let (first_account, second_account) = (first.account.clone().unwrap(), second.account.clone().unwrap());
+        let between = || vec![sent(1, &first, &second_account, 10), sent(2, &second, &first_account, 20)];
+        (first.transactions, second.transactions) = (between(), between());
+
+        let pairs = common_ownership(&[first, second], &[]);
+        assert_eq!(pairs[0].evidence.direct_transfer_count, 2);
+        assert_eq!(pairs[0].evidence.direct_volume_e8s, 200);
+        assert_eq!(pairs[0].score, DIRECT_TRANSFER_WEIGHT);
+    }
+
+    #[test]
+    fn outflows_after_the_same_mint_are_synchronised_within_the_window() {
+        let [mut first, mut second] =
+            [PRINCIPALS[0], PRINCIPALS[1]].map(|principal| provider(&principal[..5], principal));
+        let mints = [1_000, 100_000];
+        first.transactions = vec![
+            sent(1, &first, "xx", 1_100),
+            sent(2, &first, "xx", 1_050),
+            sent(3, &first, "xx", 100_000),
+            // before the first mint
+            sent(4, &first, "xx", 500),
+        ];
+        second.transactions = vec![
+            sent(5, &second, "yy", 1_050 + SYNC_WINDOW_SECONDS),
+            sent(6, &second, "yy", 100_001 + SYNC_WINDOW_SECONDS),
+        ];
+
+        assert_eq!(outflow_delays(&first, &mints), BTreeMap::from([(1_000, 50), (100_000, 0)]));
+        let rewards = mints.map(|mint| reward(mint, None, None));
+        let pairs = common_ownership(&[first, second], &rewards);
+        let synchronised = &pairs[0].evidence.synchronised_outflows;
+        assert_eq!(synchronised.len(), 1);
+        assert_eq!((synchronised[0].mint_timestamp_seconds, synchronised[0].first_seconds_after_mint), (1_000, 50));
+        assert_eq!(synchronised[0].second_seconds_after_mint, 50 + SYNC_WINDOW_SECONDS);
+        assert_eq!(pairs[0].score, SYNCHRONISED_OUTFLOW_WEIGHT);
+    }
+}
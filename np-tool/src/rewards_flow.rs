@@ -0,0 +1,265 @@
+use crate::{
+    helper::principal_to_account_id,
+    labels::LabelIndex,
+    trace::{trace_cached, TraceOptions, TracePath},
+    transactions::RewardRecord,
+    AccountData, Type,
+};
+use ic_agent::Agent;
+use serde::Serialize;
+use std::collections::{BTreeSet, HashMap};
+
+///
+/// ExchangeDeposit
+///
+/// One path from a provider account into an exchange. The amount is what is
+/// left of the smallest hop along the path once shorter paths took their share.
+///
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ExchangeDeposit {
+    pub exchange: String,
+    pub exchange_account: String,
+    pub hops: usize,
+    pub amount_e8s: u64,
+    pub timestamp_nanos: Option<u64>,
+    pub seconds_since_mint: Option<u64>,
+}
+
+///
+/// ProviderExchangeFlow
+///
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ProviderExchangeFlow {
+    pub name: String,
+    pub principal: String,
+    pub accounts: Vec<String>,
+    pub minted_e8s: u64,
+    pub mint_count: usize,
+    pub direct_e8s: u64,
+    pub indirect_e8s: u64,
+    pub to_exchanges_e8s: u64,
+    pub share_of_minted: Option<f64>,
+    pub median_seconds_to_deposit: Option<u64>,
+    pub deposits: Vec<ExchangeDeposit>,
+}
+
+///
+/// RewardsToExchangesReport
+///
+
+#[derive(Debug, Serialize)]
+pub struct RewardsToExchangesReport {
+    pub max_hops: usize,
+    pub minted_e8s: u64,
+    pub direct_e8s: u64,
+    pub indirect_e8s: u64,
+    pub to_exchanges_e8s: u64,
+    pub share_of_minted: Option<f64>,
+    pub median_seconds_to_deposit: Option<u64>,
+    pub providers: Vec<ProviderExchangeFlow>,
+}
+
+// rewards_to_exchanges
+// Traces every node provider's default and reward accounts up to
+// `options.max_hops` into Cex accounts; `options.stop_at` is ignored. Account
+// histories are fetched once for all traces. See attribute for how amounts are
+// split between overlapping paths.
+pub async fn rewards_to_exchanges(
+    agent: &Agent,
+    entries: &[AccountData],
+    rewards: &[RewardRecord],
+    labels: &LabelIndex,
+    options: &TraceOptions,
+) -> Result<RewardsToExchangesReport, Box<dyn std::error::Error>> {
+    let options = TraceOptions { stop_at: vec![Type::Cex], ..options.clone() };
+    let mut history = HashMap::new();
+    let mut providers = Vec::new();
+
+    for entry in entries.iter().filter(|entry| entry.ty == Type::NodeProvider) {
+        let Some(principal) = entry.principal else { continue };
+        let principal_text = principal.to_text();
+        let mints: Vec<&RewardRecord> =
+            rewards.iter().filter(|r| r.node_provider.as_deref() == Some(principal_text.as_str())).collect();
+
+        let mut accounts = BTreeSet::from([hex::encode(principal_to_account_id(&principal, entry.subaccount))]);
+        accounts.extend(mints.iter().filter_map(|r| r.reward_account.clone()));
+
+        let mut paths = Vec::new();
+        for account in &accounts {
+            paths.extend(trace_cached(agent, account, labels, &options, &mut history).await?.paths);
+        }
+        let deposits = attribute(paths, &mints);
+
+        providers.push(provider_flow(entry, principal_text, accounts.into_iter().collect(), &mints, deposits));
+    }
+
+    let minted_e8s = providers.iter().map(|p| p.minted_e8s).sum();
+    let to_exchanges_e8s = providers.iter().map(|p| p.to_exchanges_e8s).sum();
+    let delays: Vec<u64> = providers.iter().flat_map(|p| &p.deposits).filter_map(|d| d.seconds_since_mint).collect();
+
+    Ok(RewardsToExchangesReport {
+        max_hops: options.max_hops,
+        minted_e8s,
+        direct_e8s: providers.iter().map(|p| p.direct_e8s).sum(),
+        indirect_e8s: providers.iter().map(|p| p.indirect_e8s).sum(),
+        to_exchanges_e8s,
+        share_of_minted: share(to_exchanges_e8s, minted_e8s),
+        median_seconds_to_deposit: median(delays),
+        providers,
+    })
+}
+
+// attribute
+// Paths are attributed shortest first. A hop shared by several paths carries
+// its amount once over all of them, and the provider's deposits add up to at
+// most what it was minted.
+fn attribute(mut paths: Vec<TracePath>, mints: &[&RewardRecord]) -> Vec<ExchangeDeposit> {
+    let mut remaining: u64 = mints.iter().map(|m| m.amount_e8s).sum();
+    let mut capacity: HashMap<(String, String), u64> = HashMap::new();
+    paths.sort_by_key(|path| path.hops.len());
+
+    let mut deposits = Vec::new();
+    for path in &paths {
+        let mut amount_e8s = remaining;
+        for hop in &path.hops {
+            let left = *capacity.entry((hop.from.clone(), hop.to.clone())).or_insert(hop.amount_e8s);
+            amount_e8s = amount_e8s.min(left);
+        }
+        for hop in &path.hops {
+            if let Some(left) = capacity.get_mut(&(hop.from.clone(), hop.to.clone())) {
+                *left -= amount_e8s;
+            }
+        }
+        remaining -= amount_e8s;
+        deposits.push(deposit(path, mints, amount_e8s));
+    }
+
+    deposits
+}
+
+// deposit
+// the delay is measured from the last mint before the funds first moved
+fn deposit(path: &TracePath, mints: &[&RewardRecord], amount_e8s: u64) -> ExchangeDeposit {
+    let left_at = path.hops.first().and_then(|hop| hop.first_timestamp_nanos).map(|ts| ts / 1_000_000_000);
+    let deposited_at = path.hops.last().and_then(|hop| hop.first_timestamp_nanos);
+    let last_mint = left_at.and_then(|left| mints.iter().map(|m| m.timestamp_seconds).filter(|ts| *ts <= left).max());
+
+    ExchangeDeposit {
+        exchange: path.label.name.clone(),
+        exchange_account: path.destination.clone(),
+        hops: path.hops.len(),
+        amount_e8s,
+        timestamp_nanos: deposited_at,
+        seconds_since_mint: deposited_at.zip(last_mint).map(|(ts, mint)| (ts / 1_000_000_000).saturating_sub(mint)),
+    }
+}
+
+fn provider_flow(
+    entry: &AccountData,
+    principal: String,
+    accounts: Vec<String>,
+    mints: &[&RewardRecord],
+    deposits: Vec<ExchangeDeposit>,
+) -> ProviderExchangeFlow {
+    let minted_e8s: u64 = mints.iter().map(|m| m.amount_e8s).sum();
+    let direct_e8s: u64 = deposits.iter().filter(|d| d.hops == 1).map(|d| d.amount_e8s).sum();
+    let indirect_e8s: u64 = deposits.iter().filter(|d| d.hops > 1).map(|d| d.amount_e8s).sum();
+    let to_exchanges_e8s = direct_e8s.saturating_add(indirect_e8s);
+
+    ProviderExchangeFlow {
+        name: entry.name.clone(),
+        principal,
+        accounts,
+        minted_e8s,
+        mint_count: mints.len(),
+        direct_e8s,
+        indirect_e8s,
+        to_exchanges_e8s,
+        share_of_minted: share(to_exchanges_e8s, minted_e8s),
+        median_seconds_to_deposit: median(deposits.iter().filter_map(|d| d.seconds_since_mint).collect()),
+        deposits,
+    }
+}
+
+fn share(part: u64, total: u64) -> Option<f64> {
+    (total > 0).then(|| part as f64 / total as f64)
+}
+
+fn median(mut values: Vec<u64>) -> Option<u64> {
+    values.sort_unstable();
+    values.get(values.len() / 2).copied()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{labels::Label, trace::TraceHop};
+
+    fn mint(amount_e8s: u64) -> RewardRecord {
+        RewardRecord {
+            timestamp_seconds: 0,
+            node_provider: None,
+            amount_e8s,
+            reward_account: None,
+            xdr_permyriad_per_icp: None,
+        }
+    }
+
+    fn path(exchange: &str, hops: &[(&str, &str, u64)]) -> TracePath {
+        TracePath {
+            destination: exchange.to_string(),
+            label: Label { name: exchange.to_string(), ty: Type::Cex },
+            hops: hops
+                .iter()
+                .map(|(from, to, amount_e8s)| TraceHop {
+                    from: from.to_string(),
+                    to: to.to_string(),
+                    amount_e8s: *amount_e8s,
+                    transfer_count: 1,
+                    first_tx_id: 0,
+                    first_timestamp_nanos: None,
+                })
+                .collect(),
+        }
+    }
+
+    fn paths() -> Vec<TracePath> {
+        vec![
+            path("cex1", &[("p", "x", 80), ("x", "cex1", 50)]),
+            path("cex2", &[("p", "x", 80), ("x", "cex2", 60)]),
+            path("cex3", &[("p", "cex3", 30)]),
+        ]
+    }
+
+    fn amounts(deposits: &[ExchangeDeposit]) -> Vec<(&str, u64)> {
+        deposits.iter().map(|d| (d.exchange.as_str(), d.amount_e8s)).collect()
+    }
+
+    #[test]
+    fn shared_hops_are_attributed_once() {
+        let minted = mint(1_000);
+        let deposits = attribute(paths(), &[&minted]);
+
+        // p -> x carries 80 for both exchanges behind x together
+        assert_eq!(amounts(&deposits), [("cex3", 30), ("cex1", 50), ("cex2", 30)]);
+    }
+
+    #[test]
+    fn deposits_are_capped_at_minted_rewards() {
+        let minted = mint(100);
+        let deposits = attribute(paths(), &[&minted]);
+        assert_eq!(amounts(&deposits), [("cex3", 30), ("cex1", 50), ("cex2", 20)]);
+
+        let flow = provider_flow(
+            &AccountData::new("Provider", "aa", Type::NodeProvider).unwrap(),
+            String::new(),
+            Vec::new(),
+            &[&minted],
+            deposits,
+        );
+        assert_eq!((flow.direct_e8s, flow.indirect_e8s, flow.to_exchanges_e8s), (30, 70, 100));
+        assert_eq!(flow.share_of_minted, Some(1.0));
+    }
+}
@@ -0,0 +1,193 @@
+use crate::{helper::principal_to_account_id, AccountData, Type};
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+///
+/// SnsParticipation
+///
+/// One swap participation claimed by an SNS_PARTICIPANTS name.
+///
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SnsParticipation {
+    pub participant: String,
+    pub principal: String,
+    pub account: String,
+    pub sns: String,
+    pub sns_root: String,
+    pub amount_e8s: u64,
+}
+
+///
+/// SnsTotal
+///
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SnsTotal {
+    pub sns: String,
+    pub sns_root: String,
+    pub participant_count: usize,
+    pub amount_e8s: u64,
+}
+
+///
+/// SnsParticipationReport
+///
+
+#[derive(Debug, Serialize)]
+pub struct SnsParticipationReport {
+    pub totals: Vec<SnsTotal>,
+    pub participations: Vec<SnsParticipation>,
+}
+
+// participations
+// Parses every SNS participant name against the SNS list. Names are either
+// "<SNS> SNS <amount>" or "SNS: <SNS> <amount>, <SNS> <amount>, ..." with
+// amounts in ICP such as "12k" or "3.5k"; SNS names may be short aliases.
+pub fn participations(
+    participants: &[AccountData],
+    snses: &[(&str, &str)],
+    aliases: &[(&str, &str)],
+) -> Result<Vec<SnsParticipation>, String> {
+    let mut records = Vec::new();
+
+    for participant in participants.iter().filter(|entry| entry.ty == Type::SnsParticipant) {
+        let principal = participant.principal.ok_or_else(|| format!("{}: not a principal", participant.name))?;
+        let claims = parse_name(&participant.name).map_err(|e| format!("{}: {e}", participant.name))?;
+
+        for (short, amount_e8s) in claims {
+            let name = aliases.iter().find(|(alias, _)| *alias == short).map_or(short, |(_, name)| name);
+            let (sns, sns_root) = snses
+                .iter()
+                .find(|(sns, _)| *sns == name)
+                .ok_or_else(|| format!("{}: unknown SNS {short}", participant.name))?;
+
+            records.push(SnsParticipation {
+                participant: participant.name.clone(),
+                principal: principal.to_text(),
+                account: hex::encode(principal_to_account_id(&principal, participant.subaccount)),
+                sns: sns.to_string(),
+                sns_root: sns_root.to_string(),
+                amount_e8s,
+            });
+        }
+    }
+
+    Ok(records)
+}
+
+// report
+// totals per SNS, largest first
+pub fn report(participations: Vec<SnsParticipation>) -> SnsParticipationReport {
+    let mut totals: BTreeMap<&str, SnsTotal> = BTreeMap::new();
+    for record in &participations {
+        let total = totals.entry(&record.sns).or_insert_with(|| SnsTotal {
+            sns: record.sns.clone(),
+            sns_root: record.sns_root.clone(),
+            participant_count: 0,
+            amount_e8s: 0,
+        });
+        total.participant_count += 1;
+        total.amount_e8s = total.amount_e8s.saturating_add(record.amount_e8s);
+    }
+
+    let mut totals: Vec<SnsTotal> = totals.into_values().collect();
+    totals.sort_by_key(|total| std::cmp::Reverse(total.amount_e8s));
+
+    SnsParticipationReport { totals, participations }
+}
+
+// parse_name
+fn parse_name(name: &str) -> Result<Vec<(&str, u64)>, String> {
+    if let Some(list) = name.strip_prefix("SNS:") {
+        return list
+            .split(',')
+            .map(|claim| {
+                let (sns, amount) = claim.trim().rsplit_once(' ').ok_or("expected \"<SNS> <amount>\"")?;
+                Ok((sns.trim(), parse_amount(amount)?))
+            })
+            .collect();
+    }
+
+    let (sns, amount) = name.split_once(" SNS ").ok_or("expected \"<SNS> SNS <amount>\" or \"SNS: ...\"")?;
+    Ok(vec![(sns.trim(), parse_amount(amount.trim())?)])
+}
+
+// parse_amount
+// ICP amount with an optional k or m suffix, as e8s
+fn parse_amount(amount: &str) -> Result<u64, String> {
+    let (number, multiplier) = match amount.strip_suffix(['k', 'K']) {
+        Some(number) => (number, 1_000.0),
+        None => match amount.strip_suffix(['m', 'M']) {
+            Some(number) => (number, 1_000_000.0),
+            None => (amount, 1.0),
+        },
+    };
+    let icp: f64 = number.parse().map_err(|_| format!("invalid amount {amount}"))?;
+    if !icp.is_finite() || icp <= 0.0 {
+        return Err(format!("invalid amount {amount}"));
+    }
+
+    Ok((icp * multiplier * 100_000_000.0).round() as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_amount_suffixes() {
+        assert_eq!(parse_amount("12"), Ok(1_200_000_000));
+        assert_eq!(parse_amount("3.5k"), Ok(350_000_000_000));
+        assert_eq!(parse_amount("2K"), Ok(200_000_000_000));
+        assert_eq!(parse_amount("1.2m"), Ok(120_000_000_000_000));
+    }
+
+    #[test]
+    fn parse_amount_rejects_invalid() {
+        for amount in ["", "k", "abc", "0", "-5k", "NaN", "inf"] {
+            assert!(parse_amount(amount).is_err(), "{amount} should be rejected");
+        }
+    }
+
+    #[test]
+    fn parse_name_single_claim() {
+        assert_eq!(parse_name("OpenChat SNS 12k"), Ok(vec![("OpenChat", 1_200_000_000_000)]));
+        assert_eq!(parse_name("Boom DAO SNS 500"), Ok(vec![("Boom DAO", 50_000_000_000)]));
+    }
+
+    #[test]
+    fn parse_name_claim_list() {
+        assert_eq!(
+            parse_name("SNS: DOLR 2k, WTN 5k, Boom DAO 1.5k"),
+            Ok(vec![("DOLR", 200_000_000_000), ("WTN", 500_000_000_000), ("Boom DAO", 150_000_000_000)])
+        );
+    }
+
+    #[test]
+    fn parse_name_rejects_other_names() {
+        assert!(parse_name("Binance 2").is_err());
+        assert!(parse_name("SNS: DOLR").is_err());
+        assert!(parse_name("OpenChat SNS lots").is_err());
+    }
+
+    #[test]
+    fn participations_resolve_aliases_and_roots() {
+        let participants = [AccountData::new(
+            "SNS: WTN 5k",
+            "vgrm6-ur624-kl4qd-3sukh-wssor-ztoox-ecgbu-ebvn5-f4hgu-quqyk-pae",
+            Type::SnsParticipant,
+        )
+        .unwrap()];
+        let snses = [("WaterNeuron", "jmod6-4iaaa-aaaaq-aadkq-cai")];
+        let records = participations(&participants, &snses, &[("WTN", "WaterNeuron")]).unwrap();
+
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].sns, "WaterNeuron");
+        assert_eq!(records[0].sns_root, "jmod6-4iaaa-aaaaq-aadkq-cai");
+        assert_eq!(records[0].amount_e8s, 500_000_000_000);
+
+        // without the alias the short name matches no SNS
+        assert!(participations(&participants, &snses, &[]).is_err());
+    }
+}
@@ -0,0 +1,330 @@
+use crate::{
+    canister::CanisterBackend,
+    helper::principal_to_account_id,
+    sns::SnsParticipation,
+    transactions::{fetch_extra_accounts, AccountTransactionsJson, DateRangeFilter},
+};
+use candid::{CandidType, Decode, Encode, Principal};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+#[derive(CandidType, Deserialize)]
+struct ListSnsCanistersRequest {}
+
+///
+/// SnsCanisters
+///
+/// The canisters of one SNS as listed by its root canister.
+///
+
+#[derive(Debug, Clone, CandidType, Deserialize)]
+pub struct SnsCanisters {
+    pub root: Option<Principal>,
+    pub swap: Option<Principal>,
+    pub ledger: Option<Principal>,
+    pub index: Option<Principal>,
+    pub governance: Option<Principal>,
+    pub dapps: Vec<Principal>,
+    pub archives: Vec<Principal>,
+}
+
+impl SnsCanisters {
+    // icp_accounts
+    // The ICP treasury is the governance canister's default account, and the swap
+    // holds participants' ICP in its own default account. The SNS token treasury
+    // sits under a distribution subaccount on the SNS ledger, not on the ICP ledger.
+    pub fn icp_accounts(&self) -> Vec<String> {
+        self.governance
+            .iter()
+            .chain(&self.swap)
+            .map(|principal| hex::encode(principal_to_account_id(principal, None)))
+            .collect()
+    }
+}
+
+#[derive(CandidType, Deserialize)]
+pub struct GetBuyerStateRequest {
+    pub principal_id: Option<Principal>,
+}
+
+#[derive(CandidType, Deserialize)]
+struct GetBuyerStateResponse {
+    buyer_state: Option<BuyerState>,
+}
+
+#[derive(CandidType, Deserialize)]
+struct BuyerState {
+    icp: Option<TransferableAmount>,
+}
+
+#[derive(CandidType, Deserialize)]
+struct TransferableAmount {
+    amount_e8s: u64,
+}
+
+///
+/// VerificationStatus
+///
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum VerificationStatus {
+    Match,
+    Mismatch,
+    NotFound,
+}
+
+///
+/// ParticipationCheck
+///
+/// A claimed participation next to the buyer state of the swap canister.
+///
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ParticipationCheck {
+    pub participant: String,
+    pub principal: String,
+    pub sns: String,
+    pub swap: String,
+    pub claimed_e8s: u64,
+    pub onchain_e8s: Option<u64>,
+    pub status: VerificationStatus,
+}
+
+///
+/// SnsVerificationReport
+///
+
+#[derive(Debug, Serialize)]
+pub struct SnsVerificationReport {
+    pub tolerance_percent: f64,
+    pub matched: usize,
+    pub mismatched: usize,
+    pub not_found: usize,
+    pub checks: Vec<ParticipationCheck>,
+}
+
+// list_sns_canisters
+pub async fn list_sns_canisters<B: CanisterBackend>(
+    backend: &B,
+    root: Principal,
+) -> Result<SnsCanisters, Box<dyn std::error::Error>> {
+    let arg = Encode!(&ListSnsCanistersRequest {})?;
+    let response = backend.query_bytes(root, "list_sns_canisters", arg).await?;
+
+    Ok(Decode!(&response, SnsCanisters)?)
+}
+
+// buyer_amount
+// ICP committed by the principal in the swap, None when it never participated
+async fn buyer_amount<B: CanisterBackend>(
+    backend: &B,
+    swap: Principal,
+    buyer: Principal,
+) -> Result<Option<u64>, Box<dyn std::error::Error>> {
+    let arg = Encode!(&GetBuyerStateRequest { principal_id: Some(buyer) })?;
+    let response = backend.query_bytes(swap, "get_buyer_state", arg).await?;
+    let response = Decode!(&response, GetBuyerStateResponse)?;
+
+    Ok(response.buyer_state.and_then(|state| state.icp).map(|icp| icp.amount_e8s))
+}
+
+// verify_participation
+// Looks up each SNS's swap canister through its root, then the buyer state of
+// every claimed participant. Names round amounts, so a claim matches when it is
+// within `tolerance_percent` of the amount on chain.
+pub async fn verify_participation<B: CanisterBackend>(
+    backend: &B,
+    participations: &[SnsParticipation],
+    tolerance_percent: f64,
+) -> Result<SnsVerificationReport, Box<dyn std::error::Error>> {
+    let mut swaps: HashMap<&str, Principal> = HashMap::new();
+    let mut checks = Vec::new();
+
+    for record in participations {
+        let swap = match swaps.get(record.sns_root.as_str()) {
+            Some(swap) => *swap,
+            None => {
+                let canisters = list_sns_canisters(backend, Principal::from_text(&record.sns_root)?).await?;
+                let swap = canisters.swap.ok_or_else(|| format!("{} has no swap canister", record.sns))?;
+                swaps.insert(&record.sns_root, swap);
+                swap
+            }
+        };
+
+        println!("Checking {} in the {} swap", record.participant, record.sns);
+        let onchain_e8s = buyer_amount(backend, swap, Principal::from_text(&record.principal)?).await?;
+        let status = match onchain_e8s {
+            None | Some(0) => VerificationStatus::NotFound,
+            Some(amount) if within(record.amount_e8s, amount, tolerance_percent) => VerificationStatus::Match,
+            Some(_) => VerificationStatus::Mismatch,
+        };
+
+        checks.push(ParticipationCheck {
+            participant: record.participant.clone(),
+            principal: record.principal.clone(),
+            sns: record.sns.clone(),
+            swap: swap.to_text(),
+            claimed_e8s: record.amount_e8s,
+            onchain_e8s,
+            status,
+        });
+    }
+
+    let count = |status| checks.iter().filter(|check| check.status == status).count();
+    Ok(SnsVerificationReport {
+        tolerance_percent,
+        matched: count(VerificationStatus::Match),
+        mismatched: count(VerificationStatus::Mismatch),
+        not_found: count(VerificationStatus::NotFound),
+        checks,
+    })
+}
+
+// fetch_sns_accounts
+// adds the treasury and swap histories to an SNS fetched by its root principal
+pub async fn fetch_sns_accounts<B: CanisterBackend>(
+    account_tx: &mut AccountTransactionsJson,
+    backend: &B,
+    date_filter: Option<DateRangeFilter>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let root = account_tx.principal.as_deref().ok_or_else(|| format!("{} has no root principal", account_tx.name))?;
+    let canisters = list_sns_canisters(backend, Principal::from_text(root)?).await?;
+
+    fetch_extra_accounts(account_tx, backend, &canisters.icp_accounts(), date_filter).await
+}
+
+fn within(claimed_e8s: u64, onchain_e8s: u64, tolerance_percent: f64) -> bool {
+    claimed_e8s.abs_diff(onchain_e8s) as f64 <= claimed_e8s as f64 * tolerance_percent / 100.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        canister::MemoryBackend,
+        transactions::{respond_index_page, SimplifiedTransfer},
+        AccountData, Type,
+    };
+
+    const ROOT: &str = "zxeu2-7aaaa-aaaaq-aaafa-cai";
+    const SWAP: &str = "zcdfx-6iaaa-aaaaq-aaagq-cai";
+    const GOVERNANCE: &str = "rrkah-fqaaa-aaaaa-aaaaq-cai";
+    const ALICE: &str = "vgrm6-ur624-kl4qd-3sukh-wssor-ztoox-ecgbu-ebvn5-f4hgu-quqyk-pae";
+    const BOB: &str = "3qzrp-z7r45-vzitp-ylj45-brcdc-vhiyg-xew4i-wpeqg-b2gf6-2xzzd-yqe";
+    const CAROL: &str = "mlnie-bxsfa-gv7tu-ehhvv-7i24g-b2cnn-qbxnk-q4css-g24an-yinaf-lae";
+
+    fn principal(text: &str) -> Principal {
+        Principal::from_text(text).unwrap()
+    }
+
+    fn canisters(swap: Option<&str>) -> SnsCanisters {
+        SnsCanisters {
+            root: Some(principal(ROOT)),
+            swap: swap.map(principal),
+            ledger: None,
+            index: None,
+            governance: None,
+            dapps: Vec::new(),
+            archives: Vec::new(),
+        }
+    }
+
+    fn participation(principal: &str, amount_e8s: u64) -> SnsParticipation {
+        SnsParticipation {
+            participant: format!("SNS: Test {amount_e8s}"),
+            principal: principal.to_string(),
+            account: String::new(),
+            sns: "Test".to_string(),
+            sns_root: ROOT.to_string(),
+            amount_e8s,
+        }
+    }
+
+    fn backend(swap: Option<&str>, buyers: &[(&str, Option<u64>)]) -> MemoryBackend {
+        let mut backend = MemoryBackend::default();
+        backend.respond(
+            principal(ROOT),
+            "list_sns_canisters",
+            Encode!(&ListSnsCanistersRequest {}).unwrap(),
+            Encode!(&canisters(swap)).unwrap(),
+        );
+        for (buyer, amount_e8s) in buyers {
+            let request = GetBuyerStateRequest { principal_id: Some(principal(buyer)) };
+            let response = GetBuyerStateResponse {
+                buyer_state: amount_e8s.map(|amount_e8s| BuyerState { icp: Some(TransferableAmount { amount_e8s }) }),
+            };
+            backend.respond(
+                principal(SWAP),
+                "get_buyer_state",
+                Encode!(&request).unwrap(),
+                Encode!(&response).unwrap(),
+            );
+        }
+        backend
+    }
+
+    #[tokio::test]
+    async fn verify_participation_compares_claims_with_buyer_state() {
+        let backend = backend(Some(SWAP), &[(ALICE, Some(1_020)), (BOB, Some(2_000)), (CAROL, None)]);
+        let claims = [participation(ALICE, 1_000), participation(BOB, 1_000), participation(CAROL, 1_000)];
+
+        let report = verify_participation(&backend, &claims, 5.0).await.unwrap();
+
+        let statuses: Vec<_> = report.checks.iter().map(|check| (check.status, check.onchain_e8s)).collect();
+        assert_eq!(
+            statuses,
+            [
+                (VerificationStatus::Match, Some(1_020)),
+                (VerificationStatus::Mismatch, Some(2_000)),
+                (VerificationStatus::NotFound, None)
+            ]
+        );
+        assert_eq!((report.matched, report.mismatched, report.not_found), (1, 1, 1));
+        assert!(report.checks.iter().all(|check| check.swap == SWAP));
+    }
+
+    #[tokio::test]
+    async fn verify_participation_fails_without_swap_canister() {
+        let backend = backend(None, &[]);
+
+        let err = verify_participation(&backend, &[participation(ALICE, 1_000)], 5.0).await.unwrap_err();
+
+        assert_eq!(err.to_string(), "Test has no swap canister");
+    }
+
+    fn default_account(text: &str) -> String {
+        hex::encode(principal_to_account_id(&principal(text), None))
+    }
+
+    #[test]
+    fn icp_accounts_are_the_governance_and_swap_default_accounts() {
+        let both = SnsCanisters { governance: Some(principal(GOVERNANCE)), ..canisters(Some(SWAP)) };
+
+        assert_eq!(both.icp_accounts(), [default_account(GOVERNANCE), default_account(SWAP)]);
+        assert_eq!(canisters(Some(SWAP)).icp_accounts(), [default_account(SWAP)]);
+        assert!(canisters(None).icp_accounts().is_empty());
+    }
+
+    #[tokio::test]
+    async fn fetch_sns_accounts_merges_treasury_and_swap_histories() {
+        let mut backend = MemoryBackend::default();
+        let listed = SnsCanisters { governance: Some(principal(GOVERNANCE)), ..canisters(Some(SWAP)) };
+        backend.respond(
+            principal(ROOT),
+            "list_sns_canisters",
+            Encode!(&ListSnsCanistersRequest {}).unwrap(),
+            Encode!(&listed).unwrap(),
+        );
+        respond_index_page(&mut backend, &default_account(GOVERNANCE), None, &[4, 1]);
+        respond_index_page(&mut backend, &default_account(SWAP), None, &[4, 2, 1]);
+        let mut account_tx =
+            AccountTransactionsJson::label_only(&AccountData::new("Test", ROOT, Type::Sns).unwrap(), None);
+        account_tx.transactions = vec![SimplifiedTransfer::transfer(5, "aa", "bb", 100)];
+
+        fetch_sns_accounts(&mut account_tx, &backend, None).await.unwrap();
+
+        assert_eq!(account_tx.extra_accounts, [default_account(GOVERNANCE), default_account(SWAP)]);
+        assert_eq!(account_tx.transactions.iter().map(|tx| tx.id).collect::<Vec<_>>(), [5, 4, 2, 1]);
+        assert_eq!((account_tx.balance_e8s, account_tx.oldest_tx_id), (Some(1_800), Some(1)));
+    }
+}
@@ -0,0 +1,136 @@
+use crate::{
+    labels::{Label, LabelIndex},
+    transactions::AccountTransactionsJson,
+    Type,
+};
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+
+///
+/// SpamOptions
+///
+
+#[derive(Debug, Clone, Copy)]
+pub struct SpamOptions {
+    pub max_amount_e8s: u64,
+    pub min_transfers: u64,
+    pub min_recipients: usize,
+}
+
+///
+/// SpamCandidate
+///
+/// A sender of many tiny transfers to many recipients, not yet in SPAMMERS.
+///
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SpamCandidate {
+    pub account: String,
+    pub label: Option<Label>,
+    pub tiny_transfer_count: u64,
+    pub recipient_count: usize,
+    pub tiny_volume_e8s: u64,
+    pub first_timestamp_nanos: Option<u64>,
+    pub last_timestamp_nanos: Option<u64>,
+}
+
+#[derive(Debug, Default)]
+struct Dust<'a> {
+    recipients: BTreeSet<&'a str>,
+    count: u64,
+    volume_e8s: u64,
+    first_timestamp_nanos: Option<u64>,
+    last_timestamp_nanos: Option<u64>,
+}
+
+// detect_spammers
+// Counts transfers of at most `max_amount_e8s` per sender across every fetched
+// history, each transfer once. Senders already labelled Spammer are left out.
+pub fn detect_spammers(
+    results: &[AccountTransactionsJson],
+    labels: &LabelIndex,
+    options: &SpamOptions,
+) -> Vec<SpamCandidate> {
+    let mut senders: BTreeMap<&str, Dust> = BTreeMap::new();
+    let mut seen_ids = HashSet::new();
+
+    let transfers = results.iter().flat_map(|account_tx| &account_tx.transactions);
+    for tx in transfers.filter(|tx| tx.amount_e8s <= options.max_amount_e8s && tx.from != tx.to) {
+        if !seen_ids.insert(tx.id) {
+            continue;
+        }
+
+        let dust = senders.entry(tx.from.as_str()).or_default();
+        dust.recipients.insert(&tx.to);
+        dust.count += 1;
+        dust.volume_e8s = dust.volume_e8s.saturating_add(tx.amount_e8s);
+        if let Some(ts) = tx.timestamp_nanos {
+            dust.first_timestamp_nanos = Some(dust.first_timestamp_nanos.map_or(ts, |first| first.min(ts)));
+            dust.last_timestamp_nanos = Some(dust.last_timestamp_nanos.map_or(ts, |last| last.max(ts)));
+        }
+    }
+
+    let mut candidates: Vec<SpamCandidate> = senders
+        .into_iter()
+        .filter(|(_, dust)| dust.count >= options.min_transfers && dust.recipients.len() >= options.min_recipients)
+        .filter(|(account, _)| labels.get(account).is_none_or(|label| label.ty != Type::Spammer))
+        .map(|(account, dust)| SpamCandidate {
+            account: account.to_string(),
+            label: labels.get(account).cloned(),
+            tiny_transfer_count: dust.count,
+            recipient_count: dust.recipients.len(),
+            tiny_volume_e8s: dust.volume_e8s,
+            first_timestamp_nanos: dust.first_timestamp_nanos,
+            last_timestamp_nanos: dust.last_timestamp_nanos,
+        })
+        .collect();
+    candidates.sort_by(|a, b| b.recipient_count.cmp(&a.recipient_count).then_with(|| a.account.cmp(&b.account)));
+
+    candidates
+}
+
+// spam_accounts
+// known spammers plus detected candidates, leaving out candidates labelled as
+// anything but a spammer
+pub fn spam_accounts(labels: &LabelIndex, candidates: &[SpamCandidate]) -> HashSet<String> {
+    let detected = candidates
+        .iter()
+        .filter(|candidate| labels.get(&candidate.account).is_none_or(|label| label.ty == Type::Spammer))
+        .map(|candidate| candidate.account.clone());
+
+    labels
+        .iter()
+        .filter(|(_, label)| label.ty == Type::Spammer)
+        .map(|(account, _)| account.clone())
+        .chain(detected)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candidate(account: &str) -> SpamCandidate {
+        SpamCandidate {
+            account: account.to_string(),
+            label: None,
+            tiny_transfer_count: 100,
+            recipient_count: 50,
+            tiny_volume_e8s: 100,
+            first_timestamp_nanos: None,
+            last_timestamp_nanos: None,
+        }
+    }
+
+    #[test]
+    fn spam_accounts_keep_labelled_candidates_out() {
+        let mut labels = LabelIndex::default();
+        labels.insert("known".to_string(), Label { name: "Spammer 1".to_string(), ty: Type::Spammer });
+        labels.insert("cex".to_string(), Label { name: "Binance".to_string(), ty: Type::Cex });
+        labels.insert("labelled".to_string(), Label { name: "Spammer 2".to_string(), ty: Type::Spammer });
+
+        let accounts = spam_accounts(&labels, &[candidate("cex"), candidate("labelled"), candidate("unknown")]);
+
+        assert_eq!(accounts, HashSet::from(["known", "labelled", "unknown"].map(String::from)));
+    }
+}
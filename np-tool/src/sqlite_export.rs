@@ -0,0 +1,277 @@
+use crate::{
+    labels::LabelIndex,
+    sns::SnsParticipation,
+    transactions::{AccountTransactionsJson, RewardRecord},
+    Type,
+};
+use rusqlite::{params, Connection};
+use std::{collections::HashSet, path::Path};
+
+const SCHEMA: &str = "
+CREATE TABLE entities (
+    id INTEGER PRIMARY KEY,
+    name TEXT NOT NULL,
+    entity TEXT,
+    type TEXT NOT NULL,
+    subcategory TEXT,
+    tags TEXT,
+    principal TEXT,
+    account_id TEXT,
+    balance_e8s INTEGER,
+    genesis_icp INTEGER
+);
+CREATE TABLE accounts (
+    account_id TEXT PRIMARY KEY,
+    entity_id INTEGER NOT NULL REFERENCES entities(id),
+    kind TEXT NOT NULL,
+    principal TEXT,
+    subaccount TEXT
+);
+CREATE TABLE transactions (
+    id INTEGER PRIMARY KEY,
+    op_type TEXT NOT NULL,
+    from_account TEXT NOT NULL,
+    to_account TEXT NOT NULL,
+    amount_e8s INTEGER NOT NULL,
+    fee_e8s INTEGER NOT NULL,
+    memo TEXT NOT NULL,
+    timestamp_nanos INTEGER
+);
+CREATE TABLE node_provider_rewards (
+    timestamp_seconds INTEGER NOT NULL,
+    node_provider TEXT,
+    amount_e8s INTEGER NOT NULL,
+    reward_account TEXT,
+    xdr_permyriad_per_icp INTEGER
+);
+CREATE TABLE sns_participation (
+    participant TEXT NOT NULL,
+    principal TEXT NOT NULL,
+    account_id TEXT NOT NULL,
+    sns TEXT NOT NULL,
+    sns_root TEXT NOT NULL,
+    amount_e8s INTEGER NOT NULL
+);
+CREATE TABLE labels (
+    account_id TEXT PRIMARY KEY,
+    name TEXT NOT NULL,
+    type TEXT NOT NULL
+);
+CREATE INDEX idx_entities_account ON entities(account_id);
+CREATE INDEX idx_accounts_entity ON accounts(entity_id);
+CREATE INDEX idx_transactions_from ON transactions(from_account);
+CREATE INDEX idx_transactions_to ON transactions(to_account);
+CREATE INDEX idx_transactions_timestamp ON transactions(timestamp_nanos);
+CREATE INDEX idx_rewards_provider ON node_provider_rewards(node_provider);
+CREATE INDEX idx_rewards_account ON node_provider_rewards(reward_account);
+CREATE INDEX idx_rewards_timestamp ON node_provider_rewards(timestamp_seconds);
+";
+
+// write_sqlite
+// replaces any existing database at `path`; memos are stored as text as they
+// do not fit in a signed 64 bit integer
+pub fn write_sqlite(
+    path: &Path,
+    data: &[AccountTransactionsJson],
+    rewards: &[RewardRecord],
+    participations: &[SnsParticipation],
+    labels: &LabelIndex,
+) -> Result<(), Box<dyn std::error::Error>> {
+    if path.exists() {
+        std::fs::remove_file(path)?;
+    }
+
+    let mut conn = Connection::open(path)?;
+    conn.execute_batch(SCHEMA)?;
+    let tx = conn.transaction()?;
+
+    {
+        let mut insert_entity = tx.prepare(
+            "INSERT INTO entities (name, entity, type, subcategory, tags, principal, account_id, balance_e8s, genesis_icp)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+        )?;
+        let mut insert_account = tx.prepare(
+            "INSERT OR IGNORE INTO accounts (account_id, entity_id, kind, principal, subaccount)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+        )?;
+        let mut insert_transaction = tx.prepare(
+            "INSERT OR IGNORE INTO transactions
+             (id, op_type, from_account, to_account, amount_e8s, fee_e8s, memo, timestamp_nanos)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+        )?;
+
+        let mut seen_ids = HashSet::new();
+        for account_tx in data {
+            insert_entity.execute(params![
+                account_tx.name,
+                account_tx.entity,
+                format!("{:?}", account_tx.ty),
+                account_tx.subcategory,
+                account_tx.tags.join(";"),
+                account_tx.principal,
+                account_tx.account,
+                account_tx.balance_e8s.map(|b| b as i64),
+                account_tx.genesis_icp.map(|icp| icp as i64),
+            ])?;
+            let entity_id = tx.last_insert_rowid();
+
+            // rolled up entities list each address with its own principal
+            for address in &account_tx.addresses {
+                if let Some(account) = &address.account {
+                    insert_account.execute(params![
+                        account,
+                        entity_id,
+                        "main",
+                        address.principal,
+                        address.subaccount
+                    ])?;
+                }
+                for account in &address.extra_accounts {
+                    insert_account.execute(params![
+                        account,
+                        entity_id,
+                        extra_kind(account_tx.ty),
+                        None::<String>,
+                        None::<String>
+                    ])?;
+                }
+            }
+            if let Some(account) = &account_tx.account {
+                insert_account.execute(params![
+                    account,
+                    entity_id,
+                    "main",
+                    account_tx.principal,
+                    account_tx.subaccount
+                ])?;
+            }
+            for account in &account_tx.extra_accounts {
+                insert_account.execute(params![
+                    account,
+                    entity_id,
+                    extra_kind(account_tx.ty),
+                    None::<String>,
+                    None::<String>
+                ])?;
+            }
+
+            for transfer in &account_tx.transactions {
+                if !seen_ids.insert(transfer.id) {
+                    continue;
+                }
+                insert_transaction.execute(params![
+                    transfer.id as i64,
+                    transfer.op_type,
+                    transfer.from,
+                    transfer.to,
+                    transfer.amount_e8s as i64,
+                    transfer.fee_e8s as i64,
+                    transfer.memo.to_string(),
+                    transfer.timestamp_nanos.map(|ts| ts as i64),
+                ])?;
+            }
+        }
+
+        let mut insert_reward = tx.prepare(
+            "INSERT INTO node_provider_rewards
+             (timestamp_seconds, node_provider, amount_e8s, reward_account, xdr_permyriad_per_icp)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+        )?;
+        for reward in rewards {
+            insert_reward.execute(params![
+                reward.timestamp_seconds as i64,
+                reward.node_provider,
+                reward.amount_e8s as i64,
+                reward.reward_account,
+                reward.xdr_permyriad_per_icp.map(|x| x as i64),
+            ])?;
+        }
+
+        let mut insert_participation = tx.prepare(
+            "INSERT INTO sns_participation (participant, principal, account_id, sns, sns_root, amount_e8s)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        )?;
+        for record in participations {
+            insert_participation.execute(params![
+                record.participant,
+                record.principal,
+                record.account,
+                record.sns,
+                record.sns_root,
+                record.amount_e8s as i64,
+            ])?;
+        }
+
+        let mut insert_label = tx.prepare("INSERT INTO labels (account_id, name, type) VALUES (?1, ?2, ?3)")?;
+        for (account, label) in labels.iter() {
+            insert_label.execute(params![account, label.name, format!("{:?}", label.ty)])?;
+        }
+    }
+
+    tx.commit()?;
+    Ok(())
+}
+
+// extra_kind
+// extra accounts are reward accounts, or treasury and swap accounts for an SNS
+fn extra_kind(ty: Type) -> &'static str {
+    match ty {
+        Type::Sns => "sns",
+        _ => "reward",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{labels::Label, transactions::SimplifiedTransfer, AccountData};
+
+    fn entity(name: &str, account: &str, ty: Type, extra_accounts: &[&str]) -> AccountTransactionsJson {
+        let mut account_tx = AccountTransactionsJson::label_only(&AccountData::new(name, account, ty).unwrap(), None);
+        account_tx.extra_accounts = extra_accounts.iter().map(|account| account.to_string()).collect();
+        // the same transfer as listed by both ends
+        account_tx.transactions = vec![SimplifiedTransfer::transfer(3, "ab", "bb", 100)];
+        account_tx
+    }
+
+    #[test]
+    fn write_sqlite_stores_each_transfer_once_and_joins_labels_and_rewards() {
+        let data = [entity("Alpha", "aa", Type::NodeProvider, &["ab"]), entity("Bravo", "bb", Type::Cex, &[])];
+        let rewards = [RewardRecord {
+            timestamp_seconds: 1,
+            node_provider: None,
+            amount_e8s: 500,
+            reward_account: Some("ab".to_string()),
+            xdr_permyriad_per_icp: None,
+        }];
+        let mut labels = LabelIndex::default();
+        labels.insert("bb".to_string(), Label { name: "Bravo".to_string(), ty: Type::Cex });
+        let path = std::env::temp_dir().join(format!("np-tool-sqlite-{}.db", std::process::id()));
+
+        write_sqlite(&path, &data, &rewards, &[], &labels).unwrap();
+        let conn = Connection::open(&path).unwrap();
+        let count = |table: &str| -> i64 {
+            conn.query_row(&format!("SELECT COUNT(*) FROM {table}"), [], |row| row.get(0)).unwrap()
+        };
+        let counts = ["entities", "accounts", "transactions", "node_provider_rewards", "labels"].map(count);
+        let to_label: String = conn
+            .query_row("SELECT l.name FROM transactions t JOIN labels l ON l.account_id = t.to_account", [], |row| {
+                row.get(0)
+            })
+            .unwrap();
+        let reward_entity: (String, String) = conn
+            .query_row(
+                "SELECT e.name, a.kind FROM node_provider_rewards r
+                 JOIN accounts a ON a.account_id = r.reward_account JOIN entities e ON e.id = a.entity_id",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap();
+        drop(conn);
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(counts, [2, 3, 1, 1, 1]);
+        assert_eq!(to_label, "Bravo");
+        assert_eq!(reward_entity, ("Alpha".to_string(), "reward".to_string()));
+    }
+}
@@ -0,0 +1,263 @@
+use crate::{
+    canister::CanisterBackend,
+    helper::{is_valid_account_id, principal_to_account_id},
+    labels::{Label, LabelIndex},
+    transactions::{fetch_index_transactions, DateRangeFilter, SimplifiedTransfer},
+    Type,
+};
+use candid::Principal;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+
+///
+/// TraceOptions
+///
+
+#[derive(Debug, Clone)]
+pub struct TraceOptions {
+    pub max_hops: usize,
+    pub max_fan_out: usize,
+    pub min_amount_e8s: u64,
+    pub stop_at: Vec<Type>,
+    pub date_filter: Option<DateRangeFilter>,
+}
+
+///
+/// TraceHop
+///
+/// All qualifying transfers from one account to the next, after the funds arrived.
+///
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TraceHop {
+    pub from: String,
+    pub to: String,
+    pub amount_e8s: u64,
+    pub transfer_count: u64,
+    pub first_tx_id: u64,
+    pub first_timestamp_nanos: Option<u64>,
+}
+
+///
+/// TracePath
+///
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TracePath {
+    pub destination: String,
+    pub label: Label,
+    pub hops: Vec<TraceHop>,
+}
+
+///
+/// TraceReport
+///
+
+#[derive(Debug, Serialize)]
+pub struct TraceReport {
+    pub source: String,
+    pub source_label: Option<Label>,
+    pub accounts_visited: usize,
+    pub paths: Vec<TracePath>,
+}
+
+// resolve_account
+// accepts a hex account id or a principal, which maps to its default account
+pub fn resolve_account(input: &str) -> Result<String, Box<dyn std::error::Error>> {
+    if input.contains('-') {
+        let principal = Principal::from_text(input)?;
+        return Ok(hex::encode(principal_to_account_id(&principal, None)));
+    }
+    if !is_valid_account_id(input)? {
+        return Err(format!("invalid account id: {input}").into());
+    }
+
+    Ok(input.to_string())
+}
+
+// trace
+// Breadth-first over outgoing transfers. Each account is expanded once, and only
+// transfers made after the funds reached it are followed. A path ends at the
+// first labelled account whose type is in `stop_at`, or at any labelled account
+// when `stop_at` is empty.
+pub async fn trace<B: CanisterBackend>(
+    backend: &B,
+    source: &str,
+    labels: &LabelIndex,
+    options: &TraceOptions,
+) -> Result<TraceReport, Box<dyn std::error::Error>> {
+    trace_cached(backend, source, labels, options, &mut HashMap::new()).await
+}
+
+// trace_cached
+// as trace, reusing account histories fetched by earlier traces with the same
+// options and adding the ones it fetches
+pub async fn trace_cached<B: CanisterBackend>(
+    backend: &B,
+    source: &str,
+    labels: &LabelIndex,
+    options: &TraceOptions,
+    history: &mut HashMap<String, Vec<SimplifiedTransfer>>,
+) -> Result<TraceReport, Box<dyn std::error::Error>> {
+    let mut queue: VecDeque<(String, Vec<TraceHop>)> = VecDeque::from([(source.to_string(), Vec::new())]);
+    let mut visited: HashSet<String> = HashSet::from([source.to_string()]);
+    let mut accounts_visited = 0;
+    let mut paths = Vec::new();
+
+    while let Some((account, path)) = queue.pop_front() {
+        accounts_visited += 1;
+        if !history.contains_key(&account) {
+            println!("Tracing account {} (hop {})", account, path.len());
+            let since = options.date_filter.and_then(|filter| filter.start_timestamp_seconds);
+            let response = fetch_index_transactions(backend, &account, since).await?;
+            let transfers = response
+                .transactions
+                .iter()
+                .filter(|tx| options.date_filter.is_none_or(|filter| filter.contains_transaction(&tx.transaction)))
+                .filter_map(SimplifiedTransfer::from_transaction)
+                .collect();
+            history.insert(account.clone(), transfers);
+        }
+
+        let arrived_at = path.last().and_then(|hop| hop.first_timestamp_nanos).unwrap_or(0);
+        for hop in next_hops(&account, &history[&account], arrived_at, options) {
+            let mut next_path = path.clone();
+            let to = hop.to.clone();
+            next_path.push(hop);
+
+            if let Some(label) = labels.get(&to).filter(|label| is_destination(label, options)) {
+                paths.push(TracePath { destination: to, label: label.clone(), hops: next_path });
+            } else if next_path.len() < options.max_hops && visited.insert(to.clone()) {
+                queue.push_back((to, next_path));
+            }
+        }
+    }
+
+    Ok(TraceReport { source: source.to_string(), source_label: labels.get(source).cloned(), accounts_visited, paths })
+}
+
+// next_hops
+// outgoing transfers grouped by recipient, largest first, capped at max_fan_out
+fn next_hops(
+    account: &str,
+    transfers: &[SimplifiedTransfer],
+    arrived_at: u64,
+    options: &TraceOptions,
+) -> Vec<TraceHop> {
+    let mut by_recipient: BTreeMap<&str, TraceHop> = BTreeMap::new();
+
+    for tx in transfers {
+        if tx.from != account || tx.to == account || tx.amount_e8s < options.min_amount_e8s {
+            continue;
+        }
+        if tx.timestamp_nanos.is_some_and(|ts| ts < arrived_at) {
+            continue;
+        }
+
+        let hop = by_recipient.entry(tx.to.as_str()).or_insert_with(|| TraceHop {
+            from: account.to_string(),
+            to: tx.to.clone(),
+            amount_e8s: 0,
+            transfer_count: 0,
+            first_tx_id: tx.id,
+            first_timestamp_nanos: tx.timestamp_nanos,
+        });
+        hop.amount_e8s = hop.amount_e8s.saturating_add(tx.amount_e8s);
+        hop.transfer_count += 1;
+        if tx.timestamp_nanos.is_some_and(|ts| hop.first_timestamp_nanos.is_none_or(|first| ts < first)) {
+            hop.first_tx_id = tx.id;
+            hop.first_timestamp_nanos = tx.timestamp_nanos;
+        }
+    }
+
+    let mut hops: Vec<TraceHop> = by_recipient.into_values().collect();
+    hops.sort_by_key(|hop| std::cmp::Reverse(hop.amount_e8s));
+    hops.truncate(options.max_fan_out);
+
+    hops
+}
+
+fn is_destination(label: &Label, options: &TraceOptions) -> bool {
+    options.stop_at.is_empty() || options.stop_at.contains(&label.ty)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::canister::MemoryBackend;
+
+    fn options(max_hops: usize, max_fan_out: usize, stop_at: Vec<Type>) -> TraceOptions {
+        TraceOptions { max_hops, max_fan_out, min_amount_e8s: 10, stop_at, date_filter: None }
+    }
+
+    fn transfer(id: u64, from: &str, to: &str, amount_e8s: u64, timestamp_nanos: u64) -> SimplifiedTransfer {
+        SimplifiedTransfer {
+            timestamp_nanos: Some(timestamp_nanos),
+            ..SimplifiedTransfer::transfer(id, from, to, amount_e8s)
+        }
+    }
+
+    // src -> a -> b -> cex, with a transfer a -> x made before the funds arrived
+    fn history() -> HashMap<String, Vec<SimplifiedTransfer>> {
+        let chain = [
+            ("src", vec![transfer(1, "src", "a", 100, 10)]),
+            ("a", vec![transfer(1, "src", "a", 100, 10), transfer(2, "a", "x", 100, 5), transfer(3, "a", "b", 90, 20)]),
+            ("b", vec![transfer(3, "a", "b", 90, 20), transfer(4, "b", "cex", 80, 30)]),
+            ("x", Vec::new()),
+        ];
+        chain.into_iter().map(|(account, transfers)| (account.to_string(), transfers)).collect()
+    }
+
+    fn labels() -> LabelIndex {
+        let mut labels = LabelIndex::default();
+        labels.insert("b".to_string(), Label { name: "Bravo".to_string(), ty: Type::Identified });
+        labels.insert("cex".to_string(), Label { name: "Exchange".to_string(), ty: Type::Cex });
+        labels
+    }
+
+    // the histories are all cached, so the empty backend is never asked
+    async fn destinations(options: &TraceOptions) -> Vec<(String, usize)> {
+        let report = trace_cached(&MemoryBackend::default(), "src", &labels(), options, &mut history()).await.unwrap();
+        report.paths.iter().map(|path| (path.destination.clone(), path.hops.len())).collect()
+    }
+
+    #[test]
+    fn next_hops_group_by_recipient_above_the_minimum_and_cap_the_fan_out() {
+        let transfers = [
+            transfer(1, "aa", "bb", 30, 10),
+            transfer(2, "aa", "bb", 40, 5),
+            transfer(3, "aa", "cc", 50, 10),
+            transfer(4, "aa", "dd", 5, 10),
+            transfer(5, "aa", "ee", 20, 10),
+            transfer(6, "aa", "aa", 500, 10),
+            transfer(7, "ff", "aa", 500, 10),
+            transfer(8, "aa", "gg", 500, 1),
+        ];
+
+        let hops = next_hops("aa", &transfers, 2, &options(5, 2, Vec::new()));
+        let hops: Vec<(&str, u64, u64, u64)> =
+            hops.iter().map(|hop| (hop.to.as_str(), hop.amount_e8s, hop.transfer_count, hop.first_tx_id)).collect();
+        assert_eq!(hops, [("bb", 70, 2, 2), ("cc", 50, 1, 3)]);
+    }
+
+    #[tokio::test]
+    async fn trace_follows_transfers_made_after_arrival_to_a_stop_type() {
+        assert_eq!(destinations(&options(5, 10, vec![Type::Cex])).await, [("cex".to_string(), 3)]);
+    }
+
+    #[tokio::test]
+    async fn trace_ends_at_any_labelled_account_without_stop_types() {
+        assert_eq!(destinations(&options(5, 10, Vec::new())).await, [("b".to_string(), 2)]);
+    }
+
+    #[tokio::test]
+    async fn trace_stops_at_the_hop_limit() {
+        assert!(destinations(&options(2, 10, vec![Type::Cex])).await.is_empty());
+
+        let report =
+            trace_cached(&MemoryBackend::default(), "src", &labels(), &options(2, 10, vec![Type::Cex]), &mut history())
+                .await
+                .unwrap();
+        assert_eq!(report.accounts_visited, 2);
+    }
+}
@@ -1,8 +1,13 @@
 use std::collections::{HashMap, HashSet};
 
 use crate::{
-    helper::{is_valid_account_id, principal_to_account_id},
-    AccountData,
+    canister::CanisterBackend,
+    entities::{entity_name, EntityAddress},
+    export_filter::SpamSummary,
+    flows::CounterpartyFlow,
+    helper::{icrc1_account_text, is_valid_account_id, principal_to_account_id},
+    labels::Label,
+    AccountData, Type,
 };
 use candid::{CandidType, Decode, Encode};
 use ic_agent::{export::Principal, Agent};
@@ -10,14 +15,31 @@ use icp_ledger::AccountIdentifier;
 use serde::{Deserialize, Serialize};
 
 const INDEX_CANISTER_ID: &str = "qhbym-qaaaa-aaaaa-aaafq-cai";
-const GOVERNANCE_CANISTER_ID: &str = "rrkah-fqaaa-aaaaa-aaaaq-cai";
+pub const GOVERNANCE_CANISTER_ID: &str = "rrkah-fqaaa-aaaaa-aaaaq-cai";
+const INDEX_MAX_RESULTS: u64 = 10000;
 
-#[derive(CandidType, Deserialize)]
+#[derive(CandidType, Deserialize, Debug, Clone)]
 pub struct Account {
     pub owner: Principal,
     pub subaccount: Option<serde_bytes::ByteBuf>,
 }
 
+impl Account {
+    // from_entry
+    pub fn from_entry(entry: &AccountData) -> Option<Self> {
+        Some(Self {
+            owner: entry.principal?,
+            subaccount: entry.subaccount.map(|sub| serde_bytes::ByteBuf::from(sub.to_vec())),
+        })
+    }
+
+    // to_address
+    // ICRC-1 textual form, as in the address book
+    pub fn to_address(&self) -> String {
+        icrc1_account_text(&self.owner, self.subaccount.as_ref().map(|sub| sub.as_slice()))
+    }
+}
+
 #[derive(CandidType, Deserialize)]
 pub struct GetAccountTransactionsArgs {
     pub max_results: u64,
@@ -136,10 +158,28 @@ struct XdrConversionRate {
     timestamp_seconds: Option<u64>,
 }
 
-#[derive(Debug, Serialize, Deserialize, CandidType)]
-struct DateRangeFilter {
-    start_timestamp_seconds: Option<u64>,
-    end_timestamp_seconds: Option<u64>,
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, CandidType)]
+pub struct DateRangeFilter {
+    pub start_timestamp_seconds: Option<u64>,
+    pub end_timestamp_seconds: Option<u64>,
+}
+
+impl DateRangeFilter {
+    // contains
+    // both bounds are inclusive
+    pub fn contains(&self, timestamp_seconds: u64) -> bool {
+        self.start_timestamp_seconds.is_none_or(|start| timestamp_seconds >= start)
+            && self.end_timestamp_seconds.is_none_or(|end| timestamp_seconds <= end)
+    }
+
+    // contains_transaction
+    // transactions without a timestamp only pass an unbounded filter
+    pub fn contains_transaction(&self, tx: &Transaction) -> bool {
+        match &tx.timestamp {
+            Some(ts) => self.contains(ts.timestamp_nanos / 1_000_000_000),
+            None => self.start_timestamp_seconds.is_none() && self.end_timestamp_seconds.is_none(),
+        }
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, CandidType)]
@@ -194,17 +234,213 @@ pub struct SimplifiedTransfer {
     pub op_type: String,
     pub from: String,
     pub to: String,
+    #[serde(default)]
+    pub id: u64,
+    #[serde(default)]
+    pub amount_e8s: u64,
+    #[serde(default)]
+    pub fee_e8s: u64,
+    #[serde(default)]
+    pub memo: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub icrc1_memo: Option<String>,
+    #[serde(default)]
+    pub timestamp_nanos: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub from_label: Option<Label>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub to_label: Option<Label>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub counterparty: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub counterparty_label: Option<Label>,
+}
+
+impl SimplifiedTransfer {
+    // from_transaction
+    // only transfers are kept, labels are filled in later from the LabelIndex
+    pub fn from_transaction(tx_with_id: &TransactionWithId) -> Option<Self> {
+        let tx = &tx_with_id.transaction;
+        let Operation::Transfer { to, fee, from, amount, .. } = &tx.operation else {
+            return None;
+        };
+
+        Some(Self {
+            op_type: get_operation_type(&tx.operation).to_string(),
+            from: from.clone(),
+            to: to.clone(),
+            id: tx_with_id.id,
+            amount_e8s: amount.e8s,
+            fee_e8s: fee.e8s,
+            memo: tx.memo,
+            icrc1_memo: tx.icrc1_memo.as_ref().map(hex::encode),
+            timestamp_nanos: tx.timestamp.as_ref().map(|ts| ts.timestamp_nanos),
+            from_label: None,
+            to_label: None,
+            counterparty: None,
+            counterparty_label: None,
+        })
+    }
+}
+
+#[cfg(test)]
+impl SimplifiedTransfer {
+    // transfer
+    // a plain transfer for tests, struct update syntax sets anything else
+    pub fn transfer(id: u64, from: &str, to: &str, amount_e8s: u64) -> Self {
+        Self {
+            op_type: "Transfer".to_string(),
+            from: from.to_string(),
+            to: to.to_string(),
+            id,
+            amount_e8s,
+            fee_e8s: 10_000,
+            memo: 0,
+            icrc1_memo: None,
+            timestamp_nanos: None,
+            from_label: None,
+            to_label: None,
+            counterparty: None,
+            counterparty_label: None,
+        }
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct AccountTransactionsJson {
     pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub entity: Option<String>,
     pub principal: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub subaccount: Option<String>,
     pub account: Option<String>,
-    pub ty: String,
-    extra_accounts: Vec<String>,
+    pub ty: Type,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub subcategory: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub genesis_icp: Option<u64>,
+    pub extra_accounts: Vec<String>,
     pub transactions: Vec<SimplifiedTransfer>,
     pub oldest_tx_id: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub valuation: Option<Valuation>,
+    #[serde(default)]
+    pub counterparties: Vec<CounterpartyFlow>,
+    #[serde(default)]
+    pub balance_e8s: Option<u64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub spam: Option<SpamSummary>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub addresses: Vec<EntityAddress>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scanned_blocks: Option<ScannedBlocks>,
+}
+
+impl AccountTransactionsJson {
+    // owns
+    // true for the main account and any extra reward accounts
+    pub fn owns(&self, account: &str) -> bool {
+        self.account.as_deref() == Some(account) || self.extra_accounts.iter().any(|a| a == account)
+    }
+
+    // label_only
+    // an address book entry exported without fetching its history
+    pub fn label_only(account_data: &AccountData, valuation: Option<Valuation>) -> Self {
+        Self {
+            name: account_data.name.clone(),
+            entity: Some(entity_name(&account_data.name)),
+            principal: account_data.principal.map(|p| p.to_text()),
+            subaccount: account_data.subaccount.map(hex::encode),
+            account: account_data.account_id(),
+            ty: account_data.ty,
+            subcategory: account_data.subcategory.clone(),
+            tags: account_data.tags.clone(),
+            genesis_icp: account_data.genesis_icp,
+            extra_accounts: Vec::new(),
+            transactions: Vec::new(),
+            oldest_tx_id: None,
+            valuation,
+            counterparties: Vec::new(),
+            balance_e8s: None,
+            spam: None,
+            addresses: Vec::new(),
+            scanned_blocks: None,
+        }
+    }
+}
+
+///
+/// ScannedBlocks
+///
+/// The ledger blocks [start, end) a history was built from instead of the index.
+///
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct ScannedBlocks {
+    pub start: u64,
+    pub end: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Valuation {
+    pub price_icp: Option<f64>,
+    pub price_usd: Option<f64>,
+    pub liquidity_usd: f64,
+}
+
+///
+/// RewardRecord
+///
+/// One node provider reward from one monthly distribution.
+///
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RewardRecord {
+    pub timestamp_seconds: u64,
+    pub node_provider: Option<String>,
+    pub amount_e8s: u64,
+    pub reward_account: Option<String>,
+    pub xdr_permyriad_per_icp: Option<u64>,
+}
+
+// reward_records
+// the reward account is the explicit RewardToAccount destination, falling back
+// to the node provider's registered reward account
+pub fn reward_records(rewards_response: &ListNodeProviderRewardsResponse) -> Vec<RewardRecord> {
+    let mut records = Vec::new();
+
+    for monthly in &rewards_response.rewards {
+        let xdr_permyriad_per_icp = monthly.xdr_conversion_rate.as_ref().and_then(|rate| rate.xdr_permyriad_per_icp);
+        for reward in &monthly.rewards {
+            let to_account = match &reward.reward_mode {
+                Some(RewardMode::RewardToAccount(to)) => to.to_account.as_ref(),
+                _ => None,
+            };
+            let node_provider = reward.node_provider.as_ref();
+
+            records.push(RewardRecord {
+                timestamp_seconds: monthly.timestamp,
+                node_provider: node_provider.and_then(|np| np.id).map(|id| id.to_text()),
+                amount_e8s: reward.amount_e8s,
+                reward_account: to_account
+                    .or(node_provider.and_then(|np| np.reward_account.as_ref()))
+                    .map(|account| format_account_hash(&account.hash)),
+                xdr_permyriad_per_icp,
+            });
+        }
+    }
+
+    records
+}
+
+// format_account_hash
+// governance may hand out the 28 byte hash without the checksum
+pub fn format_account_hash(hash: &[u8]) -> String {
+    let hex = hex::encode(hash);
+    process_account_hex(&hex).1.unwrap_or(hex)
 }
 
 pub fn process_account_hex(hex: &str) -> (Option<String>, Option<String>, Option<String>) {
@@ -297,8 +533,11 @@ pub fn process_rewards_data(rewards_response: ListNodeProviderRewardsResponse) -
     result
 }
 
-pub async fn fetch_nodes_rewards(agent: &Agent) -> Result<ListNodeProviderRewardsResponse, Box<dyn std::error::Error>> {
-    let request = ListNodeProviderRewardsRequest { date_filter: None };
+pub async fn fetch_nodes_rewards(
+    agent: &Agent,
+    date_filter: Option<DateRangeFilter>,
+) -> Result<ListNodeProviderRewardsResponse, Box<dyn std::error::Error>> {
+    let request = ListNodeProviderRewardsRequest { date_filter };
 
     // Encode the request using Candid
     let args = Encode!(&request)?;
@@ -348,16 +587,62 @@ fn get_operation_type(op: &Operation) -> &str {
     }
 }
 
+// fetch_index_page
+// newest first from `start`, up to INDEX_MAX_RESULTS
+async fn fetch_index_page<B: CanisterBackend>(
+    backend: &B,
+    account_identifier: &str,
+    start: Option<u64>,
+) -> Result<GetAccountIdentifierTransactionsResponse, Box<dyn std::error::Error>> {
+    let request = GetAccountTransactionsArgs {
+        max_results: INDEX_MAX_RESULTS,
+        start,
+        account_identifier: account_identifier.to_string(),
+    };
+    let principal = Principal::from_text(INDEX_CANISTER_ID)?;
+
+    let response = backend.query_bytes(principal, "get_account_identifier_transactions", Encode!(&request)?).await?;
+    match Decode!(response.as_slice(), GetAccountIdentifierTransactionsResult)? {
+        GetAccountIdentifierTransactionsResult::Ok(resp) => Ok(resp),
+        GetAccountIdentifierTransactionsResult::Err(err) => Err(err.message.into()),
+    }
+}
+
+// fetch_index_transactions
+// Pages through the account's history, each page starting at the oldest id seen
+// so far, until the index's oldest_tx_id, an empty page, or a transaction older
+// than `since_seconds`. Filtering to a window is left to the caller.
+pub async fn fetch_index_transactions<B: CanisterBackend>(
+    backend: &B,
+    account_identifier: &str,
+    since_seconds: Option<u64>,
+) -> Result<GetAccountIdentifierTransactionsResponse, Box<dyn std::error::Error>> {
+    let mut history = fetch_index_page(backend, account_identifier, None).await?;
+
+    while let Some(last) = history.transactions.last() {
+        let last_seconds = last.transaction.timestamp.as_ref().map(|ts| ts.timestamp_nanos / 1_000_000_000);
+        let past_since = since_seconds.zip(last_seconds).is_some_and(|(since, seconds)| seconds < since);
+        if past_since || history.oldest_tx_id.is_none_or(|oldest| oldest >= last.id) {
+            break;
+        }
+        let last = last.id;
+        let page = fetch_index_page(backend, account_identifier, Some(last)).await?;
+        let count = history.transactions.len();
+        history.transactions.extend(page.transactions.into_iter().filter(|tx| tx.id < last));
+        if history.transactions.len() == count {
+            break;
+        }
+    }
+
+    Ok(history)
+}
+
 pub async fn fetch_account_transactions(
     account_data: AccountData,
     agent: &Agent,
+    date_filter: Option<DateRangeFilter>,
 ) -> Result<AccountTransactionsJson, Box<dyn std::error::Error>> {
-    let account_identifier = if let Some(principal) = account_data.principal {
-        let account_id = principal_to_account_id(&principal, None);
-        hex::encode(account_id)
-    } else if let Some(account_hex) = account_data.account.clone() {
-        account_hex
-    } else {
+    let Some(account_identifier) = account_data.account_id() else {
         return Err("No principal or account id provided".into());
     };
 
@@ -366,19 +651,13 @@ pub async fn fetch_account_transactions(
     }
     println!("Fetching txs data for account {}", account_identifier);
 
-    let request =
-        GetAccountTransactionsArgs { max_results: 10000, start: None, account_identifier: account_identifier.clone() };
-    let args = Encode!(&request)?;
-    let principal = Principal::from_text(INDEX_CANISTER_ID)?;
-
-    let response_bytes = agent.query(&principal, "get_account_identifier_transactions").with_arg(args).call().await?;
-    let result = Decode!(response_bytes.as_slice(), GetAccountIdentifierTransactionsResult)?;
-    let (_balance, mut transactions, oldest_tx_id) = match result {
-        GetAccountIdentifierTransactionsResult::Ok(resp) => (resp.balance, resp.transactions, resp.oldest_tx_id),
-        GetAccountIdentifierTransactionsResult::Err(err) => return Err(err.message.into()),
-    };
+    let since = date_filter.and_then(|filter| filter.start_timestamp_seconds);
+    let response = fetch_index_transactions(agent, &account_identifier, since).await?;
+    let (mut balance, mut transactions, oldest_tx_id) =
+        (response.balance, response.transactions, response.oldest_tx_id);
 
-    let rewards = fetch_nodes_rewards(agent).await?;
+    // the reward account can predate the window, so look it up over all rewards
+    let rewards = fetch_nodes_rewards(agent, None).await?;
     let rewards_by_principal = process_rewards_data(rewards);
     let extra_account: Option<String> = if let Some(principal) = account_data.principal {
         rewards_by_principal.get(&Principal::to_string(&principal)).and_then(|rd| rd.clone().reward_account_formatted)
@@ -391,49 +670,165 @@ pub async fn fetch_account_transactions(
         // Only proceed if the extra account is different from the main account identifier.
         if extra_acc != account_identifier {
             extra_accounts.push(extra_acc.clone());
-            let extra_request =
-                GetAccountTransactionsArgs { max_results: 10000, start: None, account_identifier: extra_acc };
-            let extra_args = Encode!(&extra_request)?;
-            let extra_response_bytes =
-                agent.query(&principal, "get_account_identifier_transactions").with_arg(extra_args).call().await?;
-            let extra_result = Decode!(extra_response_bytes.as_slice(), GetAccountIdentifierTransactionsResult)?;
-            match extra_result {
-                GetAccountIdentifierTransactionsResult::Ok(extra_resp) => {
-                    transactions.extend(extra_resp.transactions);
-                    // Optionally, combine balances if needed.
-                }
-                GetAccountIdentifierTransactionsResult::Err(err) => {
-                    return Err(err.message.into());
-                }
-            }
+            let extra_response = fetch_index_transactions(agent, &extra_acc, since).await?;
+            transactions.extend(extra_response.transactions);
+            balance = balance.saturating_add(extra_response.balance);
         }
     }
 
-    let simplified_transactions: Vec<SimplifiedTransfer> = transactions
-        .into_iter()
-        .filter_map(|tx_with_id| {
-            if let Operation::Transfer { to, from, .. } = &tx_with_id.transaction.operation {
-                Some(SimplifiedTransfer {
-                    op_type: get_operation_type(&tx_with_id.transaction.operation).to_string(),
-                    from: from.clone(),
-                    to: to.clone(),
-                })
-            } else {
-                None
-            }
-        })
-        .collect();
+    if let Some(filter) = date_filter {
+        transactions.retain(|tx_with_id| filter.contains_transaction(&tx_with_id.transaction));
+    }
+
+    let simplified_transactions: Vec<SimplifiedTransfer> =
+        transactions.iter().filter_map(SimplifiedTransfer::from_transaction).collect();
 
     // 5. Build the final JSON output.
     let output = AccountTransactionsJson {
+        entity: Some(entity_name(&account_data.name)),
         name: account_data.name,
         principal: account_data.principal.map(|p| p.to_text()),
+        subaccount: account_data.subaccount.map(hex::encode),
         account: Some(account_identifier),
-        ty: format!("{:?}", account_data.ty),
+        ty: account_data.ty,
+        subcategory: account_data.subcategory,
+        tags: account_data.tags,
+        genesis_icp: account_data.genesis_icp,
         transactions: simplified_transactions,
         extra_accounts,
         oldest_tx_id,
+        valuation: None,
+        counterparties: Vec::new(),
+        balance_e8s: Some(balance),
+        spam: None,
+        addresses: Vec::new(),
+        scanned_blocks: None,
     };
 
     Ok(output)
 }
+
+// fetch_extra_accounts
+// fetches more accounts of an already fetched entity and merges their transfers and balances
+pub async fn fetch_extra_accounts<B: CanisterBackend>(
+    account_tx: &mut AccountTransactionsJson,
+    backend: &B,
+    accounts: &[String],
+    date_filter: Option<DateRangeFilter>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut seen_ids: HashSet<u64> = account_tx.transactions.iter().map(|tx| tx.id).collect();
+
+    for account in accounts {
+        if account_tx.owns(account) {
+            continue;
+        }
+        println!("Fetching txs data for {} account {}", account_tx.name, account);
+        let since = date_filter.and_then(|filter| filter.start_timestamp_seconds);
+        let response = fetch_index_transactions(backend, account, since).await?;
+
+        let transfers = response
+            .transactions
+            .iter()
+            .filter(|tx| date_filter.is_none_or(|filter| filter.contains_transaction(&tx.transaction)))
+            .filter_map(SimplifiedTransfer::from_transaction)
+            .filter(|tx| seen_ids.insert(tx.id));
+        account_tx.transactions.extend(transfers);
+        account_tx.extra_accounts.push(account.clone());
+        account_tx.balance_e8s = Some(account_tx.balance_e8s.unwrap_or(0).saturating_add(response.balance));
+        account_tx.oldest_tx_id = match (account_tx.oldest_tx_id, response.oldest_tx_id) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        };
+    }
+    account_tx.transactions.sort_by_key(|tx| std::cmp::Reverse(tx.id));
+
+    Ok(())
+}
+
+// respond_index_page
+// Answers an index page request for `account` with transfers into it,
+// timestamped id * 100 seconds, a balance of 900 and 1 as the oldest id.
+#[cfg(test)]
+pub fn respond_index_page(
+    backend: &mut crate::canister::MemoryBackend,
+    account: &str,
+    start: Option<u64>,
+    ids: &[u64],
+) {
+    let transactions = ids
+        .iter()
+        .map(|id| TransactionWithId {
+            id: *id,
+            transaction: Transaction {
+                memo: 0,
+                icrc1_memo: None,
+                operation: Operation::Transfer {
+                    to: account.to_string(),
+                    fee: Tokens { e8s: 10_000 },
+                    from: "aa".to_string(),
+                    amount: Tokens { e8s: 100 },
+                    spender: None,
+                },
+                timestamp: Some(TimeStamp { timestamp_nanos: id * 100 * 1_000_000_000 }),
+                created_at_time: None,
+            },
+        })
+        .collect();
+    let response = GetAccountIdentifierTransactionsResult::Ok(GetAccountIdentifierTransactionsResponse {
+        balance: 900,
+        transactions,
+        oldest_tx_id: Some(1),
+    });
+    let request =
+        GetAccountTransactionsArgs { max_results: INDEX_MAX_RESULTS, start, account_identifier: account.to_string() };
+
+    backend.respond(
+        Principal::from_text(INDEX_CANISTER_ID).unwrap(),
+        "get_account_identifier_transactions",
+        Encode!(&request).unwrap(),
+        Encode!(&response).unwrap(),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::canister::MemoryBackend;
+
+    const ACCOUNT: &str = "3452811b67e48ea3bb803a768f3d075aa5567a865fec8c5031d821a87b4dfe47";
+
+    fn backend(pages: &[(Option<u64>, &[u64])]) -> MemoryBackend {
+        let mut backend = MemoryBackend::default();
+        for (start, ids) in pages {
+            respond_index_page(&mut backend, ACCOUNT, *start, ids);
+        }
+        backend
+    }
+
+    async fn history_ids(backend: &MemoryBackend, since_seconds: Option<u64>) -> Vec<u64> {
+        let history = fetch_index_transactions(backend, ACCOUNT, since_seconds).await.unwrap();
+        history.transactions.iter().map(|tx| tx.id).collect()
+    }
+
+    #[tokio::test]
+    async fn fetch_index_transactions_pages_down_to_the_oldest_transaction() {
+        let backend = backend(&[(None, &[9, 8, 7]), (Some(7), &[7, 6, 5]), (Some(5), &[4, 3, 2, 1])]);
+
+        assert_eq!(history_ids(&backend, None).await, [9, 8, 7, 6, 5, 4, 3, 2, 1]);
+    }
+
+    #[tokio::test]
+    async fn fetch_index_transactions_stops_past_the_window_start() {
+        // the page reaching back past 650s is the last one asked for
+        let backend = backend(&[(None, &[9, 8, 7]), (Some(7), &[7, 6, 5])]);
+
+        assert_eq!(history_ids(&backend, Some(650)).await, [9, 8, 7, 6, 5]);
+    }
+
+    #[tokio::test]
+    async fn fetch_index_transactions_stops_on_a_page_without_older_transactions() {
+        let backend = backend(&[(None, &[9, 8]), (Some(8), &[8])]);
+
+        assert_eq!(history_ids(&backend, None).await, [9, 8]);
+    }
+}